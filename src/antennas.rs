@@ -0,0 +1,252 @@
+//! The resonant antennas from day 8.
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use crate::template::{ANSI_RED, ANSI_RESET};
+
+/// Position `(x, y)`, signed as antinodes can fall off the map.
+pub type Position = (i64, i64);
+
+/// Which positions in line with two antennas get an antinode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Harmonics {
+    /// For antennas `a` and `b`, harmonic `k` puts antinodes at `b + k * (b - a)` and
+    /// `a - k * (b - a)`, so harmonic 0 is on the antennas themselves.
+    Multiples {
+        first: usize,
+        /// Last harmonic, or `None` to go on until the bounds check fails.
+        last: Option<usize>,
+    },
+    /// Every grid position in line with two antennas, including the ones between them when their
+    /// distance is a multiple of a smaller grid step.
+    InLine,
+}
+
+impl Harmonics {
+    /// Antinodes twice as far from one antenna as from the other.
+    pub const PART_ONE: Harmonics = Harmonics::Multiples {
+        first: 1,
+        last: Some(1),
+    };
+    /// Antinodes at every position in line, the antennas included.
+    pub const PART_TWO: Harmonics = Harmonics::InLine;
+}
+
+/// Reads `k`, `first..last` or `first..`, both ends included, or `line` for
+/// [`Harmonics::InLine`].
+impl FromStr for Harmonics {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "line" {
+            return Ok(Harmonics::InLine);
+        }
+        let number = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|e| format!("invalid harmonic \"{n}\": {e}"))
+        };
+        let (first, last) = match s.split_once("..") {
+            Some((first, "")) => (number(first)?, None),
+            Some((first, last)) => (number(first)?, Some(number(last)?)),
+            None => (number(s)?, Some(number(s)?)),
+        };
+        if last.is_some_and(|last| last < first) {
+            return Err(format!("empty harmonic range \"{s}\""));
+        }
+        Ok(Harmonics::Multiples { first, last })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Antennas {
+    pub width: usize,
+    pub height: usize,
+    /// Antenna positions for each frequency, in row-major order.
+    pub frequencies: BTreeMap<char, Vec<Position>>,
+}
+
+/// Reads the map. Only letters and digits are antennas, so the pictures of the puzzle text with
+/// `#` antinodes drawn in can be read as well.
+pub fn parse(input: &str) -> Antennas {
+    let mut frequencies = BTreeMap::<char, Vec<Position>>::new();
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            if c.is_ascii_alphanumeric() {
+                frequencies.entry(c).or_default().push((x as i64, y as i64));
+            }
+        }
+    }
+    Antennas {
+        width: input.lines().next().map_or(0, str::len),
+        height: input.lines().count(),
+        frequencies,
+    }
+}
+
+impl Antennas {
+    pub fn in_bounds(&self, (x, y): Position) -> bool {
+        (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y)
+    }
+
+    /// Antinodes of every frequency for the given harmonics.
+    ///
+    /// Each direction stops at the first position failing `in_bounds`, so unlimited harmonics
+    /// need a check that fails eventually.
+    pub fn antinodes(
+        &self,
+        harmonics: Harmonics,
+        in_bounds: impl Fn(Position) -> bool,
+    ) -> BTreeMap<char, BTreeSet<Position>> {
+        let mut antinodes = BTreeMap::new();
+        for (&frequency, antennas) in &self.frequencies {
+            let found: &mut BTreeSet<Position> = antinodes.entry(frequency).or_default();
+            for (i, &a) in antennas.iter().enumerate() {
+                for &b in &antennas[i + 1..] {
+                    let delta = (b.0 - a.0, b.1 - a.1);
+                    let (delta, first, last) = match harmonics {
+                        Harmonics::Multiples { first, last } => (delta, first, last),
+                        Harmonics::InLine => {
+                            let gcd = num::integer::gcd(delta.0, delta.1);
+                            let step = (delta.0 / gcd, delta.1 / gcd);
+                            found.extend((1..gcd).map(|t| (a.0 + t * step.0, a.1 + t * step.1)));
+                            (step, 0, None)
+                        }
+                    };
+                    for (from, sign) in [(b, 1), (a, -1)] {
+                        let mut k = first;
+                        while last.is_none_or(|last| k <= last) {
+                            let scale = sign * k as i64;
+                            let p = (from.0 + scale * delta.0, from.1 + scale * delta.1);
+                            if !in_bounds(p) {
+                                break;
+                            }
+                            found.insert(p);
+                            k += 1;
+                        }
+                    }
+                }
+            }
+        }
+        antinodes
+    }
+
+    /// The map with antinodes as `#`, in red when `ansi` is set. Antennas are drawn over the
+    /// antinodes on the same position, as in the puzzle text.
+    pub fn render(&self, antinodes: &BTreeMap<char, BTreeSet<Position>>, ansi: bool) -> String {
+        let mut cells = vec![vec!['.'; self.width]; self.height];
+        for &(x, y) in antinodes.values().flatten() {
+            if self.in_bounds((x, y)) {
+                cells[y as usize][x as usize] = '#';
+            }
+        }
+        for (&frequency, antennas) in &self.frequencies {
+            for &(x, y) in antennas {
+                cells[y as usize][x as usize] = frequency;
+            }
+        }
+        let mut out = String::new();
+        for row in cells {
+            for c in row {
+                match (ansi, c) {
+                    (true, '#') => out += &format!("{ANSI_RED}#{ANSI_RESET}"),
+                    _ => out.push(c),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Positions holding an antinode of any frequency.
+pub fn distinct(antinodes: &BTreeMap<char, BTreeSet<Position>>) -> BTreeSet<Position> {
+    antinodes.values().flatten().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distinct, parse, Harmonics};
+
+    /// Checks that drawing the antinodes of the antennas of `picture` gives `picture` back.
+    fn assert_redraws(picture: &str, harmonics: Harmonics) {
+        let antennas = parse(picture);
+        let antinodes = antennas.antinodes(harmonics, |p| antennas.in_bounds(p));
+        assert_eq!(antennas.render(&antinodes, false), picture);
+    }
+
+    #[test]
+    fn redraws_puzzle_pictures() {
+        assert_redraws(
+            "..........\n...#......\n..........\n....a.....\n..........\n.....a....\n..........\n......#...\n..........\n..........\n",
+            Harmonics::PART_ONE,
+        );
+        assert_redraws(
+            "..........\n...#......\n#.........\n....a.....\n........a.\n.....a....\n..#.......\n......A...\n..........\n..........\n",
+            Harmonics::PART_ONE,
+        );
+        assert_redraws(
+            "......#....#\n...#....0...\n....#0....#.\n..#....0....\n....0....#..\n.#....A.....\n...#........\n#......#....\n........A...\n.........A..\n..........#.\n..........#.\n",
+            Harmonics::PART_ONE,
+        );
+        assert_redraws(
+            "T....#....\n...T......\n.T....#...\n.........#\n..#.......\n..........\n...#......\n..........\n....#.....\n..........\n",
+            Harmonics::PART_TWO,
+        );
+        assert_redraws(
+            "##....#....#\n.#.#....0...\n..#.#0....#.\n..##...0....\n....0....#..\n.#...#A....#\n...#..#.....\n#....#.#....\n..#.....A...\n....#....A..\n.#........#.\n...#......##\n",
+            Harmonics::PART_TWO,
+        );
+    }
+
+    #[test]
+    fn finds_positions_between_distant_antennas() {
+        let antennas = parse("a..\n...\n..a\n");
+        let antinodes = antennas.antinodes(Harmonics::PART_TWO, |p| antennas.in_bounds(p));
+        assert_eq!(
+            antinodes[&'a'].iter().copied().collect::<Vec<_>>(),
+            [(0, 0), (1, 1), (2, 2)]
+        );
+        // Spacing (2, 4) puts (1, 2) on the line between the antennas.
+        let far = parse("b.....\n......\n......\n......\n..b...\n......\n");
+        let antinodes = far.antinodes(Harmonics::PART_TWO, |p| far.in_bounds(p));
+        assert_eq!(
+            antinodes[&'b'].iter().copied().collect::<Vec<_>>(),
+            [(0, 0), (1, 2), (2, 4)]
+        );
+        // Harmonics step by whole multiples of the spacing, with or without a last one.
+        let tall =
+            parse("b.....\n......\n......\n......\n..b...\n......\n......\n......\n......\n");
+        let antinodes = |harmonics: &str| {
+            let antinodes = tall.antinodes(harmonics.parse().unwrap(), |p| tall.in_bounds(p));
+            antinodes[&'b'].iter().copied().collect::<Vec<_>>()
+        };
+        assert_eq!(antinodes("0..1"), [(0, 0), (2, 4), (4, 8)]);
+        assert_eq!(antinodes("0.."), [(0, 0), (2, 4), (4, 8)]);
+        assert_eq!(antinodes("1.."), [(4, 8)]);
+        assert_eq!(antinodes("1..2"), [(4, 8)]);
+        assert_eq!(antinodes("line"), [(0, 0), (1, 2), (2, 4), (3, 6), (4, 8)]);
+    }
+
+    #[test]
+    fn groups_by_frequency() {
+        let antennas = parse("..........\n..........\n..........\n....a.....\n........a.\n.....a....\n..........\n......A...\n..........\n..........\n");
+        let antinodes = antennas.antinodes(Harmonics::PART_ONE, |p| antennas.in_bounds(p));
+        assert_eq!(antinodes[&'a'].len(), 4);
+        assert!(antinodes[&'A'].is_empty());
+        // One of the `a` antinodes is hidden under the `A` antenna.
+        assert!(antinodes[&'a'].contains(&(6, 7)));
+
+        let first_three: Harmonics = "1..3".parse().unwrap();
+        let unbounded = antennas.antinodes(first_three, |_| true);
+        assert_eq!(unbounded[&'a'].len(), 3 * 6);
+        assert_eq!(distinct(&unbounded).len(), 3 * 6);
+        assert_eq!(
+            "2..".parse(),
+            Ok(Harmonics::Multiples {
+                first: 2,
+                last: None
+            })
+        );
+        assert!("3..1".parse::<Harmonics>().is_err());
+    }
+}
@@ -1,53 +1,23 @@
-advent_of_code::solution!(2);
-
-fn parse_input(input: &str) -> Vec<Vec<i32>> {
-    input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|num| num.parse().unwrap())
-                .collect()
-        })
-        .collect()
-}
+use advent_of_code::reports::{check, parse, Verdict};
 
-fn is_valid(report: &Vec<i32>) -> bool {
-    let direction = (report[1] - report[0]).signum();
-    if direction == 0 {
-        return false;
-    }
-
-    for (x, y) in report.iter().zip(report.iter().skip(1)) {
-        if (y - x).signum() != direction {
-            return false;
-        }
-        if (y - x).abs() > 3 {
-            return false;
-        }
-    }
-    true
-}
+advent_of_code::solution!(2);
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let reports = parse_input(input);
-    Some(reports.iter().filter(|&report| is_valid(report)).count() as u32)
+    let reports = parse(input);
+    Some(
+        reports
+            .iter()
+            .filter(|report| check(report) == Verdict::Safe)
+            .count() as u32,
+    )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let reports = parse_input(input);
+    let reports = parse(input);
     Some(
         reports
             .iter()
-            .filter(|&report| {
-                if is_valid(report) {
-                    return true;
-                }
-                (0..report.len()).any(|i| {
-                    let mut report_small = report.clone();
-                    report_small.remove(i);
-                    is_valid(&report_small)
-                })
-            })
+            .filter(|report| check(report) != Verdict::Unsafe)
             .count() as u32,
     )
 }
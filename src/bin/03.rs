@@ -1,39 +1,18 @@
-use regex::Regex;
+use advent_of_code::mull::Interpreter;
 advent_of_code::solution!(3);
 
-fn parse_input(input: &str) -> Vec<(u32, u32)> {
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-    re.captures_iter(input)
-        .map(|cap| (cap[1].parse().unwrap(), cap[2].parse().unwrap()))
-        .collect()
+fn run(input: &str, conditionals: bool) -> Option<u64> {
+    Interpreter::new(conditionals)
+        .run(input.as_bytes(), None)
+        .ok()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let input = parse_input(input);
-    input.iter().map(|(a, b)| a * b).sum::<u32>().into()
-}
-
-fn parse_input_2(input: &str) -> Vec<(u32, u32)> {
-    let re_do = Regex::new(r"(?s)don't\(\).*?do\(\)").unwrap();
-    let input = re_do.replace_all(&input, "#");
-
-    let re_last_dont = Regex::new(r"(?s)don't\(\).*$").unwrap();
-    let input = re_last_dont.replace(&input, "#");
-
-    let re_numbers = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-    re_numbers
-        .captures_iter(&input)
-        .map(|cap| (cap[1].parse().unwrap(), cap[2].parse().unwrap()))
-        .collect()
+pub fn part_one(input: &str) -> Option<u64> {
+    run(input, false)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let input = parse_input_2(input);
-    input
-        .iter()
-        .map(|(a, b)| (a * b) as u64)
-        .sum::<u64>()
-        .into()
+    run(input, true)
 }
 
 #[cfg(test)]
@@ -1,185 +1,391 @@
 use std::collections::HashSet;
-use std::hash::Hash;
-use std::ops::Add;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use advent_of_code::grid::{Grid, Point, DIRECTIONS};
 advent_of_code::solution!(6);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: i32,
-    y: i32,
+/// Where the guard ends up when walking straight from a cell, holding the last cell reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Jump {
+    /// The guard stops in front of an obstacle.
+    Stop(Point),
+    /// The guard walks off the map after this cell.
+    Exit(Point),
 }
 
-impl Add<Position> for Position {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        Position {
-            x: self.x + other.x,
-            y: self.y + other.y,
+impl Jump {
+    fn last(self) -> Point {
+        match self {
+            Jump::Stop(point) | Jump::Exit(point) => point,
         }
     }
 }
 
-fn in_bound(position: &Position, width: usize, height: usize) -> bool {
-    position.x >= 0 && position.x < width as i32 && position.y >= 0 && position.y < height as i32
+struct Lab {
+    obstacles: Grid<bool>,
+    guard: Point,
+    /// Jump from every cell in each of the four [`DIRECTIONS`].
+    jumps: Grid<[Jump; 4]>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+fn parse_input(input: &str) -> Lab {
+    let map = Grid::parse(input);
+    let guard = map
+        .iter()
+        .find(|(_, &c)| c == '^')
+        .map(|(point, _)| point)
+        .expect("guard on the map");
+    let obstacles = map.map(|&c| c == '#');
+    let jumps = jump_table(&obstacles);
+    Lab {
+        obstacles,
+        guard,
+        jumps,
+    }
 }
 
-impl Add<Direction> for Position {
-    type Output = Self;
-
-    fn add(self, other: Direction) -> Self {
-        match other {
-            Direction::Up => Position {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Direction::Down => Position {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Direction::Left => Position {
-                x: self.x - 1,
-                y: self.y,
-            },
-            Direction::Right => Position {
-                x: self.x + 1,
-                y: self.y,
-            },
+/// Computes every jump from the jump of the next cell, visiting cells so that the next cell in
+/// the direction of the jump is always done first.
+fn jump_table(obstacles: &Grid<bool>) -> Grid<[Jump; 4]> {
+    let (width, height) = (obstacles.width(), obstacles.height());
+    let mut jumps = Grid::new(width, height, [Jump::Exit((0, 0)); 4]);
+    for (d, &(dx, dy)) in DIRECTIONS.iter().enumerate() {
+        for y in 0..height {
+            let y = if dy > 0 { height - 1 - y } else { y };
+            for x in 0..width {
+                let x = if dx > 0 { width - 1 - x } else { x };
+                jumps[(x, y)][d] = match obstacles.step((x, y), (dx, dy)) {
+                    None => Jump::Exit((x, y)),
+                    Some(next) if obstacles[next] => Jump::Stop((x, y)),
+                    Some(next) => jumps[next][d],
+                };
+            }
         }
     }
+    jumps
 }
-fn turn_right(direction: &Direction) -> Direction {
-    match direction {
-        Direction::Up => Direction::Right,
-        Direction::Right => Direction::Down,
-        Direction::Down => Direction::Left,
-        Direction::Left => Direction::Up,
+
+/// Number of steps from `from` to `to` walking in direction `d`, if `to` is ahead.
+fn distance(from: Point, to: Point, d: usize) -> Option<usize> {
+    let (dx, dy) = DIRECTIONS[d];
+    let along =
+        |from: usize, to: usize, delta: isize| (to as isize - from as isize) * delta.signum();
+    match (dx, dy) {
+        (0, _) if from.0 == to.0 => usize::try_from(along(from.1, to.1, dy)).ok(),
+        (_, 0) if from.1 == to.1 => usize::try_from(along(from.0, to.0, dx)).ok(),
+        _ => None,
     }
 }
 
-fn parse_input(input: &str) -> (Position, Direction, HashSet<Position>) {
-    let mut guard = Position { x: 0, y: 0 };
-    let mut blocks = HashSet::<Position>::new();
-
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            match c {
-                '#' => blocks
-                    .insert(Position {
-                        x: j as i32,
-                        y: i as i32,
-                    })
-                    .into(),
-                '^' => {
-                    guard = Position {
-                        x: j as i32,
-                        y: i as i32,
-                    };
-                    None
-                }
-                _ => None,
-            };
+impl Lab {
+    /// Jump from `from` in direction `d` once `block` is added to the map. Only jumps whose
+    /// path crosses `block` change, so the table is patched on the fly instead of rebuilt.
+    fn jump(&self, from: Point, d: usize, block: Point) -> Jump {
+        let jump = self.jumps[from][d];
+        let (dx, dy) = DIRECTIONS[d];
+        match (distance(from, block, d), distance(from, jump.last(), d)) {
+            (Some(to_block), Some(reach)) if (1..=reach).contains(&to_block) => {
+                let back = to_block as isize - 1;
+                Jump::Stop((
+                    from.0.wrapping_add_signed(dx * back),
+                    from.1.wrapping_add_signed(dy * back),
+                ))
+            }
+            _ => jump,
         }
     }
 
-    (guard, Direction::Up, blocks)
-}
-
-fn simulate(
-    mut guard: Position,
-    mut direction: Direction,
-    blocks: &HashSet<Position>,
-    width: usize,
-    height: usize,
-) -> HashSet<Position> {
-    let mut visited = HashSet::<Position>::new();
-    loop {
-        visited.insert(guard);
-        let next_guard = guard + direction;
-        if blocks.contains(&next_guard) {
-            direction = turn_right(&direction);
-            continue;
+    /// Cells of the guard's path in visiting order, each with the cell and direction the guard
+    /// had just before stepping on it. The starting cell is not included.
+    fn path(&self) -> Vec<(Point, Point, usize)> {
+        let mut seen = HashSet::from([self.guard]);
+        let mut path = vec![];
+        let (mut guard, mut d) = (self.guard, 0);
+        while let Some(next) = self.obstacles.step(guard, DIRECTIONS[d]) {
+            if self.obstacles[next] {
+                d = (d + 1) % 4;
+                continue;
+            }
+            if seen.insert(next) {
+                path.push((next, guard, d));
+            }
+            guard = next;
         }
+        path
+    }
 
-        if in_bound(&next_guard, width, height) {
-            guard = next_guard;
-        } else {
-            break;
-        }
+    /// Whether the guard, at `guard` facing `d`, walks in a loop once `block` is added. Only
+    /// the states in front of an obstacle are recorded, in `visited` which must be all clear.
+    fn is_looping(
+        &self,
+        mut guard: Point,
+        mut d: usize,
+        block: Point,
+        visited: &mut [u64],
+    ) -> bool {
+        let looping = loop {
+            match self.jump(guard, d, block) {
+                Jump::Exit(_) => break false,
+                Jump::Stop(stop) => {
+                    let bit = self.obstacles.index_of(stop) * 4 + d;
+                    if visited[bit / 64] & (1 << (bit % 64)) != 0 {
+                        break true;
+                    }
+                    visited[bit / 64] |= 1 << (bit % 64);
+                    guard = stop;
+                    d = (d + 1) % 4;
+                }
+            }
+        };
+        visited.fill(0);
+        looping
+    }
+
+    /// Counts the cells of the path where a new obstacle traps the guard in a loop, splitting
+    /// the candidates between `threads` scoped threads.
+    fn count_loops(&self, threads: NonZeroUsize) -> usize {
+        let path = self.path();
+        let words = (self.obstacles.width() * self.obstacles.height() * 4).div_ceil(64);
+        let chunk = path.len().div_ceil(threads.get()).max(1);
+        thread::scope(|scope| {
+            let workers: Vec<_> = path
+                .chunks(chunk)
+                .map(|candidates| {
+                    scope.spawn(move || {
+                        let mut visited = vec![0; words];
+                        candidates
+                            .iter()
+                            .filter(|&&(block, guard, d)| {
+                                self.is_looping(guard, d, block, &mut visited)
+                            })
+                            .count()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("worker panicked"))
+                .sum()
+        })
     }
-    visited
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (guard, direction, blocks) = parse_input(input);
-    let width = input.lines().next()?.len();
-    let height = input.lines().count();
+    let lab = parse_input(input);
+    Some(lab.path().len() as u32 + 1)
+}
 
-    let visited_positions = simulate(guard, direction, &blocks, width, height);
-    Some(visited_positions.len() as u32)
+pub fn part_two(input: &str) -> Option<u32> {
+    let lab = parse_input(input);
+    let threads = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+    Some(lab.count_loops(threads) as u32)
 }
 
-fn is_looping(
-    mut guard: Position,
-    mut direction: Direction,
-    blocks: &HashSet<Position>,
-    width: usize,
-    height: usize,
-) -> bool {
-    let mut visited = HashSet::<(Position, Direction)>::new();
-    loop {
-        if visited.contains(&(guard, direction)) {
-            break true;
+/// The solver before the jump table, kept unchanged to check and time the new one against.
+#[cfg(test)]
+mod baseline {
+    use std::collections::HashSet;
+    use std::hash::Hash;
+    use std::ops::Add;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Position {
+        x: i32,
+        y: i32,
+    }
+
+    impl Add<Position> for Position {
+        type Output = Self;
+
+        fn add(self, other: Self) -> Self {
+            Position {
+                x: self.x + other.x,
+                y: self.y + other.y,
+            }
         }
-        visited.insert((guard, direction));
-        let next_guard = guard + direction;
-        if blocks.contains(&next_guard) {
-            direction = turn_right(&direction);
-            continue;
+    }
+
+    fn in_bound(position: &Position, width: usize, height: usize) -> bool {
+        position.x >= 0
+            && position.x < width as i32
+            && position.y >= 0
+            && position.y < height as i32
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Direction {
+        Up,
+        Down,
+        Left,
+        Right,
+    }
+
+    impl Add<Direction> for Position {
+        type Output = Self;
+
+        fn add(self, other: Direction) -> Self {
+            match other {
+                Direction::Up => Position {
+                    x: self.x,
+                    y: self.y - 1,
+                },
+                Direction::Down => Position {
+                    x: self.x,
+                    y: self.y + 1,
+                },
+                Direction::Left => Position {
+                    x: self.x - 1,
+                    y: self.y,
+                },
+                Direction::Right => Position {
+                    x: self.x + 1,
+                    y: self.y,
+                },
+            }
         }
+    }
+    fn turn_right(direction: &Direction) -> Direction {
+        match direction {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
 
-        if in_bound(&next_guard, width, height) {
-            guard = next_guard;
-        } else {
-            break false;
+    fn parse_input(input: &str) -> (Position, Direction, HashSet<Position>) {
+        let mut guard = Position { x: 0, y: 0 };
+        let mut blocks = HashSet::<Position>::new();
+
+        for (i, line) in input.lines().enumerate() {
+            for (j, c) in line.chars().enumerate() {
+                match c {
+                    '#' => blocks
+                        .insert(Position {
+                            x: j as i32,
+                            y: i as i32,
+                        })
+                        .into(),
+                    '^' => {
+                        guard = Position {
+                            x: j as i32,
+                            y: i as i32,
+                        };
+                        None
+                    }
+                    _ => None,
+                };
+            }
         }
+
+        (guard, Direction::Up, blocks)
     }
-}
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (guard, direction, mut blocks) = parse_input(input);
-    let width = input.lines().next()?.len();
-    let height = input.lines().count();
-
-    let mut loop_count = 0;
-    let visited_positions = simulate(guard, direction, &blocks, width, height);
-    for new_block in visited_positions {
-        if blocks.contains(&new_block) || new_block == guard {
-            continue;
+    fn simulate(
+        mut guard: Position,
+        mut direction: Direction,
+        blocks: &HashSet<Position>,
+        width: usize,
+        height: usize,
+    ) -> HashSet<Position> {
+        let mut visited = HashSet::<Position>::new();
+        loop {
+            visited.insert(guard);
+            let next_guard = guard + direction;
+            if blocks.contains(&next_guard) {
+                direction = turn_right(&direction);
+                continue;
+            }
+
+            if in_bound(&next_guard, width, height) {
+                guard = next_guard;
+            } else {
+                break;
+            }
         }
+        visited
+    }
+
+    fn is_looping(
+        mut guard: Position,
+        mut direction: Direction,
+        blocks: &HashSet<Position>,
+        width: usize,
+        height: usize,
+    ) -> bool {
+        let mut visited = HashSet::<(Position, Direction)>::new();
+        loop {
+            if visited.contains(&(guard, direction)) {
+                break true;
+            }
+            visited.insert((guard, direction));
+            let next_guard = guard + direction;
+            if blocks.contains(&next_guard) {
+                direction = turn_right(&direction);
+                continue;
+            }
 
-        blocks.insert(new_block);
-        if is_looping(guard, direction, &blocks, width, height) {
-            loop_count += 1;
+            if in_bound(&next_guard, width, height) {
+                guard = next_guard;
+            } else {
+                break false;
+            }
         }
-        blocks.remove(&new_block);
     }
 
-    Some(loop_count)
+    pub fn count_loops(input: &str) -> Option<u32> {
+        let (guard, direction, mut blocks) = parse_input(input);
+        let width = input.lines().next()?.len();
+        let height = input.lines().count();
+
+        let mut loop_count = 0;
+        let visited_positions = simulate(guard, direction, &blocks, width, height);
+        for new_block in visited_positions {
+            if blocks.contains(&new_block) || new_block == guard {
+                continue;
+            }
+
+            blocks.insert(new_block);
+            if is_looping(guard, direction, &blocks, width, height) {
+                loop_count += 1;
+            }
+            blocks.remove(&new_block);
+        }
+
+        Some(loop_count)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroUsize;
+    use std::time::Instant;
+
     use super::*;
 
+    /// Map of `size` by `size` cells with about one obstacle in 20 and the guard in the middle.
+    fn random_map(size: usize) -> String {
+        let mut seed: u64 = 2024;
+        let mut map = String::new();
+        for y in 0..size {
+            for x in 0..size {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                map.push(if (x, y) == (size / 2, size / 2) {
+                    '^'
+                } else if (seed >> 33).is_multiple_of(20) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            map.push('\n');
+        }
+        map
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -191,4 +397,43 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(6));
     }
+
+    #[test]
+    fn matches_baseline() {
+        let map = random_map(40);
+        let lab = parse_input(&map);
+        let expected = baseline::count_loops(&map).unwrap() as usize;
+        assert_eq!(lab.count_loops(NonZeroUsize::MIN), expected);
+        assert_eq!(lab.count_loops(NonZeroUsize::new(4).unwrap()), expected);
+    }
+
+    /// Run with `cargo test --release --bin 06 -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn benchmark_against_baseline() {
+        let map = random_map(130);
+
+        let start = Instant::now();
+        let expected = baseline::count_loops(&map).unwrap() as usize;
+        let before = start.elapsed();
+
+        let start = Instant::now();
+        let lab = parse_input(&map);
+        assert_eq!(lab.count_loops(NonZeroUsize::MIN), expected);
+        let single = start.elapsed();
+
+        let start = Instant::now();
+        assert_eq!(part_two(&map), Some(expected as u32));
+        let parallel = start.elapsed();
+
+        println!("baseline:         {before:?}");
+        println!(
+            "jump table:       {single:?} ({:.0}x)",
+            before.as_secs_f64() / single.as_secs_f64()
+        );
+        println!(
+            "jump table, threaded: {parallel:?} ({:.0}x)",
+            before.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 advent_of_code::solution!(7);
 
 #[derive(Debug)]
@@ -6,6 +8,85 @@ struct Line {
     numbers: Vec<u64>,
 }
 
+/// Binary operator, evaluated left to right.
+#[derive(Debug, Clone, Copy)]
+struct Operator {
+    symbol: &'static str,
+    /// Computes `left op right`, `None` on overflow.
+    apply: fn(u64, u64) -> Option<u64>,
+    /// Finds `left` such that `left op right == result`, if any.
+    undo: fn(u64, u64) -> Option<Left>,
+}
+
+/// Left operands undoing an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Left {
+    One(u64),
+    /// Any left operand gives the result, as for `result == 0` and `right == 0` with `*`.
+    Any,
+}
+
+const ADD: Operator = Operator {
+    symbol: "+",
+    apply: |left, right| left.checked_add(right),
+    undo: |result, right| result.checked_sub(right).map(Left::One),
+};
+
+const MUL: Operator = Operator {
+    symbol: "*",
+    apply: |left, right| left.checked_mul(right),
+    undo: |result, right| match (result, right) {
+        (0, 0) => Some(Left::Any),
+        (_, 0) => None,
+        _ => (result % right == 0).then_some(Left::One(result / right)),
+    },
+};
+
+const CONCAT: Operator = Operator {
+    symbol: "||",
+    apply: |left, right| left.checked_mul(digits_shift(right))?.checked_add(right),
+    undo: |result, right| {
+        let shift = digits_shift(right);
+        (result % shift == right).then_some(Left::One(result / shift))
+    },
+};
+
+const PART_ONE: &[Operator] = &[ADD, MUL];
+const PART_TWO: &[Operator] = &[ADD, MUL, CONCAT];
+
+/// Power of ten with as many zeros as `n` has digits.
+fn digits_shift(n: u64) -> u64 {
+    10u64.pow(n.checked_ilog10().unwrap_or(0) + 1)
+}
+
+/// Numbers of a line joined by the operators found for it.
+#[derive(Debug, Clone)]
+struct Expression<'a> {
+    numbers: &'a [u64],
+    operators: Vec<Operator>,
+}
+
+impl Expression<'_> {
+    fn evaluate(&self) -> Option<u64> {
+        self.operators
+            .iter()
+            .zip(&self.numbers[1..])
+            .try_fold(self.numbers[0], |left, (op, &right)| {
+                (op.apply)(left, right)
+            })
+    }
+}
+
+impl Display for Expression<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.numbers[0])?;
+        for (op, right) in self.operators.iter().zip(&self.numbers[1..]) {
+            write!(f, " {} {right}", op.symbol)?;
+        }
+        Ok(())
+    }
+}
+
 fn parse_input(input: &str) -> Vec<Line> {
     input
         .lines()
@@ -18,51 +99,58 @@ fn parse_input(input: &str) -> Vec<Line> {
         .collect()
 }
 
-fn solve_line(line: &Line, allow_concat: Option<bool>) -> bool {
-    let allow_concat = allow_concat.unwrap_or(false);
-    let mut curr_values = vec![line.result];
-    for num in line.numbers.iter().skip(1).rev() {
-        let mut new_values = Vec::new();
-        let mut ten_pow_mod = 10u64.pow((*num as f64).log10().ceil() as u32);
-        if *num == ten_pow_mod {
-            ten_pow_mod *= 10;
+/// Finds operators making the line true. Works backwards from the result, undoing the last
+/// operation first, so branches die as soon as an inverse does not exist.
+fn solve_line<'a>(line: &'a Line, operators: &[Operator]) -> Option<Expression<'a>> {
+    fn unwind(target: u64, numbers: &[u64], operators: &[Operator]) -> Option<Vec<Operator>> {
+        let (&last, rest) = numbers.split_last()?;
+        if rest.is_empty() {
+            return (target == last).then(Vec::new);
         }
+        operators.iter().find_map(|op| {
+            let mut found = match (op.undo)(target, last)? {
+                Left::One(left) => unwind(left, rest, operators)?,
+                Left::Any => evaluate_any(rest[0], &rest[1..], operators)?,
+            };
+            found.push(*op);
+            Some(found)
+        })
+    }
 
-        for val in curr_values {
-            if val >= *num {
-                new_values.push(val - num);
-            }
-            if val % num == 0 {
-                new_values.push(val / num);
-            }
-
-            if allow_concat && val > 0 && val != *num && (val % ten_pow_mod) == *num {
-                new_values.push(val / ten_pow_mod);
-            }
-        }
-        curr_values = new_values;
+    /// Operators joining `left` and `numbers` into any value that does not overflow.
+    fn evaluate_any(left: u64, numbers: &[u64], operators: &[Operator]) -> Option<Vec<Operator>> {
+        let Some((&right, rest)) = numbers.split_first() else {
+            return Some(vec![]);
+        };
+        operators.iter().find_map(|op| {
+            let mut found = evaluate_any((op.apply)(left, right)?, rest, operators)?;
+            found.insert(0, *op);
+            Some(found)
+        })
     }
-    curr_values.contains(&line.numbers[0])
+
+    let expression = Expression {
+        numbers: &line.numbers,
+        operators: unwind(line.result, &line.numbers, operators)?,
+    };
+    debug_assert_eq!(expression.evaluate(), Some(line.result));
+    Some(expression)
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let lines = parse_input(input);
-    lines
+fn calibration(input: &str, operators: &[Operator]) -> u64 {
+    parse_input(input)
         .iter()
-        .filter(|line| solve_line(line, None))
+        .filter(|line| solve_line(line, operators).is_some())
         .map(|line| line.result)
-        .sum::<u64>()
-        .into()
+        .sum()
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(calibration(input, PART_ONE))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let lines = parse_input(input);
-    lines
-        .iter()
-        .filter(|line| solve_line(line, Some(true)))
-        .map(|line| line.result)
-        .sum::<u64>()
-        .into()
+    Some(calibration(input, PART_TWO))
 }
 
 #[cfg(test)]
@@ -80,4 +168,34 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn test_witness_expressions() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let lines = parse_input(&input);
+        let found: Vec<Option<String>> = lines
+            .iter()
+            .map(|line| solve_line(line, PART_TWO).map(|e| e.to_string()))
+            .collect();
+        assert_eq!(found[0].as_deref(), Some("10 * 19"));
+        assert_eq!(found[1].as_deref(), Some("81 * 40 + 27"));
+        assert_eq!(found[3].as_deref(), Some("15 || 6"));
+        assert_eq!(found[4].as_deref(), Some("6 * 8 || 6 * 15"));
+        assert_eq!(found[5], None);
+        assert!(solve_line(&lines[3], PART_ONE).is_none());
+    }
+
+    #[test]
+    fn test_zero_operands() {
+        let lines = parse_input("0: 5 0\n0: 3 4 0\n7: 7 0\n5: 5 0 0\n3: 5 0\n");
+        let found: Vec<Option<String>> = lines
+            .iter()
+            .map(|line| solve_line(line, PART_ONE).map(|e| e.to_string()))
+            .collect();
+        assert_eq!(found[0].as_deref(), Some("5 * 0"));
+        assert_eq!(found[1].as_deref(), Some("3 + 4 * 0"));
+        assert_eq!(found[2].as_deref(), Some("7 + 0"));
+        assert_eq!(found[3].as_deref(), Some("5 + 0 + 0"));
+        assert_eq!(found[4], None);
+    }
 }
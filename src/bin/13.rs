@@ -1,12 +1,12 @@
-use num::ToPrimitive;
 use regex::Regex;
-use std::collections::HashMap;
 advent_of_code::solution!(13);
 
-#[derive(Debug, Clone)]
+const PRIZE_OFFSET: i128 = 10000000000000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Button {
-    x: usize,
-    y: usize,
+    x: i128,
+    y: i128,
 }
 
 #[derive(Debug)]
@@ -16,107 +16,205 @@ struct Game {
     prize: Button,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Presses {
+    a: i128,
+    b: i128,
+}
+
+impl Presses {
+    fn tokens(&self) -> Result<i128, Unwinnable> {
+        self.a
+            .checked_mul(3)
+            .and_then(|a| a.checked_add(self.b))
+            .ok_or(Unwinnable::Overflow)
+    }
+
+    fn within(self, limit: Option<i128>) -> Result<Self, Unwinnable> {
+        match limit {
+            Some(limit) if self.a > limit || self.b > limit => Err(Unwinnable::OverLimit),
+            _ => Ok(self),
+        }
+    }
+}
+
+/// Why a claw machine cannot win its prize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unwinnable {
+    /// The buttons are collinear and the prize is not on their line.
+    OffLine,
+    /// The only solutions press a button a fractional number of times.
+    NotIntegral,
+    /// Every integer solution presses a button a negative number of times.
+    Negative,
+    /// Every solution presses a button more times than allowed.
+    OverLimit,
+    /// Intermediate values do not fit in an `i128`.
+    Overflow,
+}
+
 fn parse_input(input: &str) -> Vec<Game> {
     let re = Regex::new(r"[+=](?<X>\d+).*?[+=](?<Y>\d+)").unwrap();
     let mut games = vec![];
     for block in input.split("\n\n") {
         let matches: Vec<regex::Captures<'_>> = re.captures_iter(block).collect();
+        let button = |i: usize| Button {
+            x: matches[i].name("X").unwrap().as_str().parse().unwrap(),
+            y: matches[i].name("Y").unwrap().as_str().parse().unwrap(),
+        };
         games.push(Game {
-            a: Button {
-                x: matches[0].name("X").unwrap().as_str().parse().unwrap(),
-                y: matches[0].name("Y").unwrap().as_str().parse().unwrap(),
-            },
-            b: Button {
-                x: matches[1].name("X").unwrap().as_str().parse().unwrap(),
-                y: matches[1].name("Y").unwrap().as_str().parse().unwrap(),
-            },
-            prize: Button {
-                x: matches[2].name("X").unwrap().as_str().parse().unwrap(),
-                y: matches[2].name("Y").unwrap().as_str().parse().unwrap(),
-            },
+            a: button(0),
+            b: button(1),
+            prize: button(2),
         });
     }
     games
 }
 
-fn compute_dp(game: &Game) -> HashMap<(usize, usize), u32> {
-    let mut dp_table = HashMap::<(usize, usize), u32>::new();
-    dp_table.insert((0, 0), 0);
-
-    for (button, val) in [(&game.a, 3), (&game.b, 1)].iter() {
-        let possible_values = dp_table
-            .iter()
-            .map(|(&(x, y), &v)| ((x, y), v)) // Make a copy here
-            .collect::<Vec<((usize, usize), u32)>>();
-        for ((xx, yy), vv) in possible_values {
-            let mut k = 1;
-            while xx + button.x * k <= game.prize.x && yy + button.y * k <= game.prize.y {
-                dp_table
-                    .entry((xx + button.x * k, yy + button.y * k))
-                    .and_modify(|v| *v = *v.min(&mut (vv + val * k as u32)))
-                    .or_insert(vv + val * k as u32);
-
-                k += 1;
-            }
-        }
+/// Returns `(g, x, y)` with `u * x + v * y == g == gcd(u, v)`.
+fn extended_gcd(u: i128, v: i128) -> (i128, i128, i128) {
+    if v == 0 {
+        (u, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(v, u % v);
+        (g, y, x - (u / v) * y)
     }
-    dp_table
 }
 
-fn solve_game(game: &Game) -> Option<u64> {
-    let dp_table = compute_dp(game);
-    dp_table
-        .get(&(game.prize.x, game.prize.y))
-        .map(|v| *v as u64)
-        .into()
+/// `n / d` rounded up, for `d > 0`.
+fn div_ceil(n: i128, d: i128) -> i128 {
+    n.div_euclid(d) + i128::from(n.rem_euclid(d) != 0)
 }
 
-fn solve_game_fast(game: &Game) -> Option<u64> {
-    // println!("Game: {:?}", game);
-    let det: f64 = ((game.a.x * game.b.y) as i128 - (game.a.y * game.b.x) as i128) as f64;
-    if det.abs() < 0.0001 {
-        return None;
+/// Cheapest non-negative `(a, b)` with `a * u + b * v == w`, for `u, v, w >= 0`, pressing each
+/// button at most `limit` times if given.
+fn solve_line(u: i128, v: i128, w: i128, limit: Option<i128>) -> Result<Presses, Unwinnable> {
+    let single = |step: i128| match step {
+        0 if w == 0 => Ok(0),
+        0 => Err(Unwinnable::OffLine),
+        _ if w % step != 0 => Err(Unwinnable::NotIntegral),
+        _ => Ok(w / step),
+    };
+    match (u, v) {
+        (0, _) => Presses {
+            a: 0,
+            b: single(v)?,
+        }
+        .within(limit),
+        (_, 0) => Presses {
+            a: single(u)?,
+            b: 0,
+        }
+        .within(limit),
+        _ => {
+            let (g, x, y) = extended_gcd(u, v);
+            if w % g != 0 {
+                return Err(Unwinnable::NotIntegral);
+            }
+            let scale = w / g;
+            let a0 = x.checked_mul(scale).ok_or(Unwinnable::Overflow)?;
+            let b0 = y.checked_mul(scale).ok_or(Unwinnable::Overflow)?;
+            // Every solution is (a0 + k * v / g, b0 - k * u / g).
+            let (da, db) = (v / g, u / g);
+            // a >= 0 and b >= 0.
+            let mut k_min = div_ceil(a0.checked_neg().ok_or(Unwinnable::Overflow)?, da);
+            let mut k_max = b0.div_euclid(db);
+            if k_min > k_max {
+                return Err(Unwinnable::Negative);
+            }
+            // a <= limit and b <= limit.
+            if let Some(limit) = limit {
+                let over_a = limit.checked_sub(a0).ok_or(Unwinnable::Overflow)?;
+                let over_b = b0.checked_sub(limit).ok_or(Unwinnable::Overflow)?;
+                k_max = k_max.min(over_a.div_euclid(da));
+                k_min = k_min.max(div_ceil(over_b, db));
+                if k_min > k_max {
+                    return Err(Unwinnable::OverLimit);
+                }
+            }
+            // The cost changes by 3 * da - db per step of k, so the optimum is at a bound.
+            let slope = da
+                .checked_mul(3)
+                .and_then(|n| n.checked_sub(db))
+                .ok_or(Unwinnable::Overflow)?;
+            let k = if slope > 0 { k_min } else { k_max };
+            Ok(Presses {
+                a: k.checked_mul(da)
+                    .and_then(|n| n.checked_add(a0))
+                    .ok_or(Unwinnable::Overflow)?,
+                b: k.checked_mul(db)
+                    .and_then(|n| b0.checked_sub(n))
+                    .ok_or(Unwinnable::Overflow)?,
+            })
+        }
     }
+}
 
-    let button_a =
-        ((game.b.y * game.prize.x) as i128 - (game.b.x * game.prize.y) as i128) as f64 / det;
-    let button_b =
-        ((game.a.x * game.prize.y) as i128 - (game.a.y * game.prize.x) as i128) as f64 / det;
+fn cross(p: Button, q: Button) -> Option<i128> {
+    p.x.checked_mul(q.y)?.checked_sub(p.y.checked_mul(q.x)?)
+}
 
-    if let (Some(a), Some(b)) = (button_a.to_usize(), button_b.to_usize()) {
-        if a * game.a.x + b * game.b.x == game.prize.x
-            && a * game.a.y + b * game.b.y == game.prize.y
+/// Cheapest presses winning the prize of `game`, pressing each button at most `limit` times if
+/// given.
+fn solve_game(game: &Game, limit: Option<i128>) -> Result<Presses, Unwinnable> {
+    let det = cross(game.a, game.b).ok_or(Unwinnable::Overflow)?;
+    if det == 0 {
+        if cross(game.a, game.prize).ok_or(Unwinnable::Overflow)? != 0
+            || cross(game.b, game.prize).ok_or(Unwinnable::Overflow)? != 0
         {
-            return Some((a * 3 + b) as u64);
+            return Err(Unwinnable::OffLine);
         }
+        // Both buttons move along the prize's line, solve on an axis where they move.
+        return if game.a.x != 0 || game.b.x != 0 {
+            solve_line(game.a.x, game.b.x, game.prize.x, limit)
+        } else {
+            solve_line(game.a.y, game.b.y, game.prize.y, limit)
+        };
+    }
+
+    let a_num = cross(game.prize, game.b).ok_or(Unwinnable::Overflow)?;
+    let b_num = cross(game.a, game.prize).ok_or(Unwinnable::Overflow)?;
+    if a_num % det != 0 || b_num % det != 0 {
+        return Err(Unwinnable::NotIntegral);
     }
-    None
+    let presses = Presses {
+        a: a_num / det,
+        b: b_num / det,
+    };
+    if presses.a < 0 || presses.b < 0 {
+        return Err(Unwinnable::Negative);
+    }
+    presses.within(limit)
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let games = parse_input(input);
+/// Total tokens spent on the winnable machines.
+fn total_tokens<'a>(games: impl Iterator<Item = &'a Game>, limit: Option<i128>) -> Option<u64> {
     games
-        .iter()
-        .map(|game| solve_game(game))
-        .filter(|res| res.is_some())
-        .sum()
+        .filter_map(|game| solve_game(game, limit).ok())
+        .try_fold(0u64, |total, presses| {
+            total.checked_add(u64::try_from(presses.tokens().ok()?).ok()?)
+        })
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    total_tokens(parse_input(input).iter(), Some(100))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let games = parse_input(input);
-    games
+    let offset = |n: i128| n.checked_add(PRIZE_OFFSET);
+    let games: Option<Vec<Game>> = parse_input(input)
         .iter()
-        .map(|game| Game {
-            a: game.a.clone(),
-            b: game.b.clone(),
-            prize: Button {
-                x: game.prize.x + 10000000000000,
-                y: game.prize.y + 10000000000000,
-            },
+        .map(|game| {
+            Some(Game {
+                prize: Button {
+                    x: offset(game.prize.x)?,
+                    y: offset(game.prize.y)?,
+                },
+                ..*game
+            })
         })
-        .map(|game| solve_game_fast(&game))
-        .filter(|res| res.is_some())
-        .sum()
+        .collect();
+    total_tokens(games?.iter(), None)
 }
 
 #[cfg(test)]
@@ -134,4 +232,63 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(875318608908));
     }
+
+    #[test]
+    fn test_collinear_buttons() {
+        let game = |prize: Button| Game {
+            a: Button { x: 6, y: 4 },
+            b: Button { x: 3, y: 2 },
+            prize,
+        };
+        // Button A moves as far as two presses of B for three tokens, so only B is used.
+        assert_eq!(
+            solve_game(&game(Button { x: 30, y: 20 }), None),
+            Ok(Presses { a: 0, b: 10 })
+        );
+        assert_eq!(
+            solve_game(&game(Button { x: 31, y: 20 }), None),
+            Err(Unwinnable::OffLine)
+        );
+
+        let game = Game {
+            a: Button { x: 5, y: 5 },
+            b: Button { x: 1, y: 1 },
+            prize: Button { x: 12, y: 12 },
+        };
+        assert_eq!(solve_game(&game, None), Ok(Presses { a: 2, b: 2 }));
+
+        // The cheapest way presses B 150 times, but A and B 100 times each stays in the limit.
+        let game = Game {
+            a: Button { x: 1, y: 1 },
+            b: Button { x: 2, y: 2 },
+            prize: Button { x: 300, y: 300 },
+        };
+        assert_eq!(solve_game(&game, None), Ok(Presses { a: 0, b: 150 }));
+        assert_eq!(solve_game(&game, Some(100)), Ok(Presses { a: 100, b: 100 }));
+        assert_eq!(solve_game(&game, Some(99)), Err(Unwinnable::OverLimit));
+    }
+
+    #[test]
+    fn test_unwinnable_reasons() {
+        let games = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(solve_game(&games[1], None), Err(Unwinnable::NotIntegral));
+        let game = Game {
+            a: Button { x: 1, y: 3 },
+            b: Button { x: 3, y: 1 },
+            prize: Button { x: 1, y: 11 },
+        };
+        assert_eq!(solve_game(&game, None), Err(Unwinnable::Negative));
+        let game = Game {
+            a: Button { x: 1, y: 0 },
+            b: Button { x: 0, y: 1 },
+            prize: Button { x: 101, y: 5 },
+        };
+        assert_eq!(solve_game(&game, Some(100)), Err(Unwinnable::OverLimit));
+        let game = Game {
+            a: Button { x: i128::MAX, y: 1 },
+            b: Button { x: 1, y: i128::MAX },
+            prize: Button { x: 1, y: 1 },
+        };
+        assert_eq!(solve_game(&game, None), Err(Unwinnable::Overflow));
+    }
 }
@@ -0,0 +1,173 @@
+use std::fmt::Display;
+
+use super::Instruction;
+
+/// How an instruction reads its operand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    /// The operand value itself.
+    Literal(u8),
+    /// `0` to `3` as is, `4` to `6` for registers A to C, `7` is reserved.
+    Combo(u8),
+    /// The operand is read but not used.
+    Ignored(u8),
+}
+
+impl Instruction {
+    pub fn operand_kind(&self) -> Operand {
+        match *self {
+            Self::Bxl(n) | Self::Jnz(n) => Operand::Literal(n),
+            Self::Bxc(n) => Operand::Ignored(n),
+            Self::Adv(n) | Self::Bst(n) | Self::Out(n) | Self::Bdv(n) | Self::Cdv(n) => {
+                Operand::Combo(n)
+            }
+        }
+    }
+
+    /// Parses one line of assembly, such as `adv A`, `bxl 5` or `bxc`.
+    pub fn assemble(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let mnemonic = words.next().ok_or("empty instruction")?;
+        let opcode = (0..8)
+            .find(|&opcode| Instruction::decode(opcode, 0).map(|i| i.mnemonic()) == Some(mnemonic))
+            .ok_or_else(|| format!("unknown mnemonic {mnemonic:?}"))?;
+        let kind = Instruction::decode(opcode, 0)
+            .map(|i| i.operand_kind())
+            .expect("opcode is valid");
+
+        let operand = match (kind, words.next()) {
+            (Operand::Ignored(_), None) => 0,
+            (Operand::Combo(_), Some(register @ ("A" | "B" | "C"))) => {
+                4 + (register.as_bytes()[0] - b'A')
+            }
+            (_, Some(value)) => match value.parse::<u8>() {
+                Ok(n) if n < 4 || (n < 8 && !matches!(kind, Operand::Combo(_))) => n,
+                _ => return Err(format!("invalid operand {value:?} for {mnemonic}")),
+            },
+            (_, None) => return Err(format!("missing operand for {mnemonic}")),
+        };
+        if let Some(extra) = words.next() {
+            return Err(format!("unexpected {extra:?} after {mnemonic}"));
+        }
+
+        Ok(Instruction::decode(opcode, operand).expect("opcode is valid"))
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Operand::Literal(n) | Operand::Combo(n @ 0..=3) => write!(f, "{n}"),
+            Operand::Combo(n @ 4..=6) => write!(f, "{}", (b'A' + n - 4) as char),
+            Operand::Combo(n) => write!(f, "{n}"),
+            Operand::Ignored(0) => Ok(()),
+            Operand::Ignored(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Turns code into one instruction per line. A trailing opcode without operand and instructions
+/// with the reserved combo operand 7, which the assembler rejects, are kept as `raw <n>`.
+pub fn disassemble(code: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in code.chunks(2) {
+        let line = match *chunk {
+            [opcode, operand] => match Instruction::decode(opcode, operand) {
+                Some(instruction) if instruction.operand_kind() != Operand::Combo(7) => {
+                    instruction.to_string()
+                }
+                _ => format!("raw {opcode}\nraw {operand}"),
+            },
+            _ => chunk.iter().map(|n| format!("raw {n}")).collect(),
+        };
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
+/// Turns assembly text back into code. Blank lines and `#` comments are ignored.
+pub fn assemble(text: &str) -> Result<Vec<u8>, String> {
+    let mut code = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("raw ") {
+            match value.trim().parse::<u8>() {
+                Ok(n) if n < 8 => code.push(n),
+                _ => return Err(format!("line {}: invalid raw value {value:?}", i + 1)),
+            }
+            continue;
+        }
+        let instruction =
+            Instruction::assemble(line).map_err(|e| format!("line {}: {e}", i + 1))?;
+        code.extend([instruction.opcode(), instruction.operand()]);
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assemble, disassemble};
+    use crate::chronospatial::Program;
+
+    /// Programs given in the puzzle text.
+    const EXAMPLES: [&[u8]; 6] = [
+        &[2, 6],
+        &[5, 0, 5, 1, 5, 4],
+        &[0, 1, 5, 4, 3, 0],
+        &[1, 7],
+        &[4, 0],
+        &[0, 3, 5, 4, 3, 0],
+    ];
+
+    #[test]
+    fn disassembles_mnemonics() {
+        assert_eq!(disassemble(&[0, 1, 5, 4, 3, 0]), "adv 1\nout A\njnz 0\n");
+        assert_eq!(disassemble(&[2, 6, 1, 7, 4, 0]), "bst C\nbxl 7\nbxc\n");
+        assert_eq!(disassemble(&[5, 7, 3]), "raw 5\nraw 7\nraw 3\n");
+        assert_eq!(disassemble(&[0, 7, 3, 0]), "raw 0\nraw 7\njnz 0\n");
+    }
+
+    #[test]
+    fn round_trips_examples() {
+        for code in EXAMPLES.into_iter().chain([&[5, 7, 3][..], &[0, 7, 3, 0]]) {
+            assert_eq!(assemble(&disassemble(code)).as_deref(), Ok(code));
+        }
+    }
+
+    #[test]
+    fn assembles_programs() {
+        let text = "# prints A in octal, lowest digit first\nout A\nadv 3\njnz 0\n";
+        let code = assemble(text).unwrap();
+        assert_eq!(code, [5, 4, 0, 3, 3, 0]);
+        let program = Program {
+            registers: crate::chronospatial::Registers {
+                a: 0o1234,
+                b: 0,
+                c: 0,
+            },
+            code,
+        };
+        assert_eq!(program.run(None), Ok(vec![4, 3, 2, 1]));
+
+        assert_eq!(
+            assemble("adv D"),
+            Err("line 1: invalid operand \"D\" for adv".into())
+        );
+        assert_eq!(
+            assemble("out 7"),
+            Err("line 1: invalid operand \"7\" for out".into())
+        );
+        assert_eq!(
+            assemble("\nmul 2"),
+            Err("line 2: unknown mnemonic \"mul\"".into())
+        );
+        assert_eq!(
+            assemble("jnz"),
+            Err("line 1: missing operand for jnz".into())
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::io::{self, BufRead, Write};
+
+use super::{Machine, Stop};
+
+const HELP: &str = "\
+commands:
+  s, <enter>  execute one instruction
+  c           continue until a breakpoint or the end
+  b <pc>      toggle a breakpoint at address <pc>
+  r           show registers
+  o           show output
+  h           show this help
+  q           quit";
+
+/// Single-steps `machine` interactively, reading commands from `input` and writing to `out`.
+pub fn debug(machine: &mut Machine, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+    writeln!(out, "{HELP}")?;
+    prompt(machine, &mut out)?;
+
+    for line in input.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+        match words.next().unwrap_or("s") {
+            "s" => match machine.step() {
+                Ok(step) => writeln!(out, "{step}")?,
+                Err(stop) => writeln!(out, "{stop}")?,
+            },
+            "c" => {
+                let mut result = Ok(());
+                let stop = machine.run_with(|step| {
+                    if result.is_ok() {
+                        result = writeln!(out, "{step}");
+                    }
+                });
+                result?;
+                writeln!(out, "{stop}")?;
+            }
+            "b" => match words.next().map(str::parse::<usize>) {
+                Some(Ok(pc)) => {
+                    let state = if machine.toggle_breakpoint(pc) {
+                        "set"
+                    } else {
+                        "removed"
+                    };
+                    writeln!(out, "breakpoint {state} at {pc}")?;
+                }
+                _ => writeln!(out, "usage: b <pc>")?,
+            },
+            "r" => writeln!(out, "pc={} {}", machine.pc(), machine.registers)?,
+            "o" => writeln!(out, "{}", format_output(machine.output()))?,
+            "h" => writeln!(out, "{HELP}")?,
+            "q" => return Ok(()),
+            other => writeln!(out, "unknown command {other:?}, type h for help")?,
+        }
+        prompt(machine, &mut out)?;
+    }
+    Ok(())
+}
+
+fn prompt(machine: &Machine, out: &mut impl Write) -> io::Result<()> {
+    match machine.current() {
+        Some(instruction) => write!(out, "{:>3}: {instruction} > ", machine.pc())?,
+        None => write!(out, "{} > ", Stop::Halted)?,
+    }
+    out.flush()
+}
+
+/// Formats program output as comma-separated digits, as the puzzle expects it.
+pub fn format_output(output: &[u8]) -> String {
+    output
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::debug;
+    use crate::chronospatial::{Machine, Program};
+
+    #[test]
+    fn steps_through_program() {
+        let program: Program =
+            "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0"
+                .parse()
+                .unwrap();
+        let mut machine = Machine::new(&program);
+        let mut out = vec![];
+        debug(&mut machine, "s\nb 4\nc\nr\nq\ns\n".as_bytes(), &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("  0: adv 1  | A=729 B=0 C=0 -> A=364 B=0 C=0"));
+        assert!(out.contains("breakpoint set at 4"));
+        assert!(out.contains("breakpoint at 4"));
+        assert!(out.contains("pc=4 A=364 B=0 C=0"));
+        assert_eq!(machine.steps(), 2);
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::Operand;
+
+/// The three registers of the machine.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Registers {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+/// One decoded instruction with its raw operand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Adv(u8),
+    Bxl(u8),
+    Bst(u8),
+    Jnz(u8),
+    Bxc(u8),
+    Out(u8),
+    Bdv(u8),
+    Cdv(u8),
+}
+
+/// Initial registers and code of a program, as given in the puzzle input.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Program {
+    pub registers: Registers,
+    pub code: Vec<u8>,
+}
+
+/// Reason why the machine stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// The instruction pointer left the program.
+    Halted,
+    /// The instruction pointer reached a breakpoint, the instruction there is not executed yet.
+    Breakpoint(usize),
+    /// The step limit was reached, the program is probably looping forever.
+    StepLimit(usize),
+    /// A combo operand of 7 was found at the given address.
+    InvalidOperand(usize),
+}
+
+/// Trace entry of one executed instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub before: Registers,
+    pub after: Registers,
+    pub output: Option<u8>,
+}
+
+/// The 3-bit computer, with breakpoints and an optional step limit.
+#[derive(Clone, Debug)]
+pub struct Machine {
+    pub registers: Registers,
+    code: Vec<u8>,
+    pc: usize,
+    output: Vec<u8>,
+    steps: usize,
+    step_limit: Option<usize>,
+    breakpoints: BTreeSet<usize>,
+}
+
+impl Instruction {
+    pub fn decode(opcode: u8, operand: u8) -> Option<Self> {
+        let instruction = match opcode {
+            0 => Self::Adv(operand),
+            1 => Self::Bxl(operand),
+            2 => Self::Bst(operand),
+            3 => Self::Jnz(operand),
+            4 => Self::Bxc(operand),
+            5 => Self::Out(operand),
+            6 => Self::Bdv(operand),
+            7 => Self::Cdv(operand),
+            _ => return None,
+        };
+        Some(instruction)
+    }
+
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Self::Adv(_) => 0,
+            Self::Bxl(_) => 1,
+            Self::Bst(_) => 2,
+            Self::Jnz(_) => 3,
+            Self::Bxc(_) => 4,
+            Self::Out(_) => 5,
+            Self::Bdv(_) => 6,
+            Self::Cdv(_) => 7,
+        }
+    }
+
+    pub fn operand(&self) -> u8 {
+        match *self {
+            Self::Adv(n)
+            | Self::Bxl(n)
+            | Self::Bst(n)
+            | Self::Jnz(n)
+            | Self::Bxc(n)
+            | Self::Out(n)
+            | Self::Bdv(n)
+            | Self::Cdv(n) => n,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::Adv(_) => "adv",
+            Self::Bxl(_) => "bxl",
+            Self::Bst(_) => "bst",
+            Self::Jnz(_) => "jnz",
+            Self::Bxc(_) => "bxc",
+            Self::Out(_) => "out",
+            Self::Bdv(_) => "bdv",
+            Self::Cdv(_) => "cdv",
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operand_kind() {
+            Operand::Ignored(0) => write!(f, "{}", self.mnemonic()),
+            operand => write!(f, "{} {operand}", self.mnemonic()),
+        }
+    }
+}
+
+impl Display for Registers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "A={} B={} C={}", self.a, self.b, self.c)
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>3}: {:<6} | {} -> {}",
+            self.pc,
+            self.instruction.to_string(),
+            self.before,
+            self.after
+        )?;
+        if let Some(value) = self.output {
+            write!(f, " | out {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Stop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stop::Halted => write!(f, "halted"),
+            Stop::Breakpoint(pc) => write!(f, "breakpoint at {pc}"),
+            Stop::StepLimit(steps) => write!(f, "step limit reached after {steps} steps"),
+            Stop::InvalidOperand(pc) => write!(f, "invalid combo operand 7 at {pc}"),
+        }
+    }
+}
+
+impl FromStr for Program {
+    type Err = String;
+
+    /// Parses the puzzle input: three `Register X: n` lines, a blank line and `Program: ...`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (registers, code) = input
+            .split_once("\n\n")
+            .ok_or("expected registers and program separated by a blank line")?;
+
+        let values = registers
+            .lines()
+            .map(|line| {
+                let value = line.split_whitespace().last().unwrap_or_default();
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid register line: {line:?}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let [a, b, c] = values[..] else {
+            return Err(format!("expected 3 registers, found {}", values.len()));
+        };
+
+        let code = code
+            .trim()
+            .trim_start_matches("Program:")
+            .trim()
+            .split(',')
+            .map(|value| match value.trim().parse::<u8>() {
+                Ok(n) if n < 8 => Ok(n),
+                _ => Err(format!("invalid 3-bit number: {value:?}")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Program {
+            registers: Registers { a, b, c },
+            code,
+        })
+    }
+}
+
+impl Machine {
+    pub fn new(program: &Program) -> Self {
+        Self {
+            registers: program.registers,
+            code: program.code.clone(),
+            pc: 0,
+            output: vec![],
+            steps: 0,
+            step_limit: None,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Makes [`Machine::step`] fail with [`Stop::StepLimit`] once `limit` instructions ran.
+    pub fn with_step_limit(mut self, limit: usize) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    pub fn code(&self) -> &[u8] {
+        &self.code
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// Number of instructions executed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc + 1 >= self.code.len()
+    }
+
+    /// Instruction at the instruction pointer, `None` once halted.
+    pub fn current(&self) -> Option<Instruction> {
+        match self.code.get(self.pc..self.pc + 2) {
+            Some(&[opcode, operand]) => Instruction::decode(opcode, operand),
+            _ => None,
+        }
+    }
+
+    /// Toggles a breakpoint at `pc`, returns whether it is now set.
+    pub fn toggle_breakpoint(&mut self, pc: usize) -> bool {
+        if self.breakpoints.remove(&pc) {
+            false
+        } else {
+            self.breakpoints.insert(pc);
+            true
+        }
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    fn combo(&self, operand: u8) -> Result<u64, Stop> {
+        match operand {
+            0..=3 => Ok(operand as u64),
+            4 => Ok(self.registers.a),
+            5 => Ok(self.registers.b),
+            6 => Ok(self.registers.c),
+            _ => Err(Stop::InvalidOperand(self.pc)),
+        }
+    }
+
+    /// Divides A by a power of two. Shifting by 64 or more leaves nothing.
+    fn dv(&self, operand: u8) -> Result<u64, Stop> {
+        let shift = self.combo(operand)?;
+        Ok(u32::try_from(shift)
+            .ok()
+            .and_then(|shift| self.registers.a.checked_shr(shift))
+            .unwrap_or(0))
+    }
+
+    /// Executes the instruction at the instruction pointer.
+    pub fn step(&mut self) -> Result<Step, Stop> {
+        let instruction = self.current().ok_or(Stop::Halted)?;
+        if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+            return Err(Stop::StepLimit(self.steps));
+        }
+
+        let before = self.registers;
+        let mut output = None;
+        let mut next_pc = self.pc + 2;
+        match instruction {
+            Instruction::Adv(n) => self.registers.a = self.dv(n)?,
+            Instruction::Bxl(n) => self.registers.b ^= n as u64,
+            Instruction::Bst(n) => self.registers.b = self.combo(n)? & 0b111,
+            Instruction::Jnz(n) => {
+                if self.registers.a != 0 {
+                    next_pc = n as usize;
+                }
+            }
+            Instruction::Bxc(_) => self.registers.b ^= self.registers.c,
+            Instruction::Out(n) => output = Some((self.combo(n)? & 0b111) as u8),
+            Instruction::Bdv(n) => self.registers.b = self.dv(n)?,
+            Instruction::Cdv(n) => self.registers.c = self.dv(n)?,
+        }
+
+        let step = Step {
+            pc: self.pc,
+            instruction,
+            before,
+            after: self.registers,
+            output,
+        };
+        self.output.extend(output);
+        self.pc = next_pc;
+        self.steps += 1;
+        Ok(step)
+    }
+
+    /// Runs until the program halts or stops on a breakpoint, calling `on_step` for every
+    /// executed instruction. A breakpoint at the starting address does not stop the machine,
+    /// so that calling this again continues the execution.
+    pub fn run_with(&mut self, mut on_step: impl FnMut(&Step)) -> Stop {
+        let mut first = true;
+        loop {
+            if !first && self.breakpoints.contains(&self.pc) && !self.is_halted() {
+                return Stop::Breakpoint(self.pc);
+            }
+            first = false;
+            match self.step() {
+                Ok(step) => on_step(&step),
+                Err(stop) => return stop,
+            }
+        }
+    }
+
+    pub fn run(&mut self) -> Stop {
+        self.run_with(|_| {})
+    }
+
+    /// Same as [`Machine::run`], also returning every executed step.
+    pub fn run_traced(&mut self) -> (Vec<Step>, Stop) {
+        let mut trace = vec![];
+        let stop = self.run_with(|step| trace.push(*step));
+        (trace, stop)
+    }
+}
+
+impl Program {
+    /// Runs the program to completion and returns its output, or why it did not halt.
+    pub fn run(&self, step_limit: Option<usize>) -> Result<Vec<u8>, Stop> {
+        let mut machine = Machine::new(self);
+        machine.step_limit = step_limit;
+        match machine.run() {
+            Stop::Halted => Ok(machine.output),
+            stop => Err(stop),
+        }
+    }
+
+    /// Same as [`Program::run`] with register A overridden.
+    pub fn run_with_a(&self, a: u64, step_limit: Option<usize>) -> Result<Vec<u8>, Stop> {
+        Program {
+            registers: Registers {
+                a,
+                ..self.registers
+            },
+            code: self.code.clone(),
+        }
+        .run(step_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Instruction, Machine, Program, Registers, Stop};
+
+    fn program(a: u64, b: u64, c: u64, code: &[u8]) -> Program {
+        Program {
+            registers: Registers { a, b, c },
+            code: code.to_vec(),
+        }
+    }
+
+    #[test]
+    fn runs_puzzle_examples() {
+        let mut machine = Machine::new(&program(0, 0, 9, &[2, 6]));
+        machine.run();
+        assert_eq!(machine.registers.b, 1);
+
+        let output = program(10, 0, 0, &[5, 0, 5, 1, 5, 4]).run(None);
+        assert_eq!(output, Ok(vec![0, 1, 2]));
+
+        let mut machine = Machine::new(&program(2024, 0, 0, &[0, 1, 5, 4, 3, 0]));
+        machine.run();
+        assert_eq!(machine.output(), [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(machine.registers.a, 0);
+
+        let mut machine = Machine::new(&program(0, 2024, 43690, &[4, 0]));
+        machine.run();
+        assert_eq!(machine.registers.b, 44354);
+    }
+
+    #[test]
+    fn shifts_out_everything_past_64_bits() {
+        // cdv A with A = 2^32 + 59 + (7 << 59): the shift does not fit in 32 bits.
+        let a = (1 << 32) + 59 + (7 << 59);
+        let mut machine = Machine::new(&program(a, 0, 0, &[7, 4]));
+        machine.run();
+        assert_eq!(machine.registers.c, 0);
+
+        let mut machine = Machine::new(&program(1 << 40, 64, 0, &[0, 5]));
+        machine.run();
+        assert_eq!(machine.registers.a, 0);
+    }
+
+    #[test]
+    fn parses_program() {
+        let input = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0\n";
+        let parsed: Program = input.parse().unwrap();
+        assert_eq!(parsed, program(729, 0, 0, &[0, 1, 5, 4, 3, 0]));
+        assert_eq!(parsed.run(None), Ok(vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0]));
+        assert!("Program: 1,2".parse::<Program>().is_err());
+    }
+
+    #[test]
+    fn traces_and_stops_on_breakpoints() {
+        let mut machine = Machine::new(&program(2024, 0, 0, &[0, 1, 5, 4, 3, 0]));
+        machine.toggle_breakpoint(2);
+        assert_eq!(machine.run(), Stop::Breakpoint(2));
+        assert_eq!(machine.registers.a, 1012);
+
+        let step = machine.step().unwrap();
+        assert_eq!(step.instruction, Instruction::Out(4));
+        assert_eq!(step.output, Some(4));
+        assert_eq!(
+            step.to_string(),
+            "  2: out A  | A=1012 B=0 C=0 -> A=1012 B=0 C=0 | out 4"
+        );
+
+        let (trace, stop) = machine.run_traced();
+        assert_eq!(stop, Stop::Breakpoint(2));
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace[0].pc, 4);
+    }
+
+    #[test]
+    fn detects_infinite_loops() {
+        let looping = program(1, 0, 0, &[1, 1, 3, 0]);
+        assert_eq!(looping.run(Some(1000)), Err(Stop::StepLimit(1000)));
+        assert_eq!(
+            program(1, 0, 0, &[5, 7]).run(None),
+            Err(Stop::InvalidOperand(0))
+        );
+    }
+}
@@ -0,0 +1,10 @@
+//! The 3-bit computer from day 17.
+
+mod asm;
+pub mod debugger;
+mod machine;
+mod quine;
+
+pub use asm::*;
+pub use machine::*;
+pub use quine::*;
@@ -0,0 +1,284 @@
+use std::fmt::Display;
+
+use super::{Instruction, Machine, Operand, Program, Registers};
+
+/// Structure of a program that loops over register A, as found by [`analyse`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    /// Bits A is shifted right by in each iteration.
+    pub shift: u32,
+    /// Addresses of the instructions shifting A.
+    pub shift_pcs: Vec<usize>,
+    /// Values printed by each iteration.
+    pub outputs_per_iteration: usize,
+    /// Number of low bits of A that the first output of an iteration depends on.
+    pub dependency_bits: u32,
+}
+
+/// Why [`find_quine`] cannot handle a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Unsupported {
+    /// The program does not end with `jnz 0`, so it is not a single loop over A.
+    NoFinalJump,
+    /// A jump other than the final `jnz 0` was found at this address.
+    InnerJump(usize),
+    /// A is shifted by a register value at this address, not by a constant.
+    VariableShift(usize),
+    /// A is never shifted, so the loop only ends if A starts at 0.
+    NoShift,
+    /// The register is read before being written, so iterations depend on each other.
+    CarriedRegister(char),
+    /// An iteration prints nothing.
+    NoOutput,
+    /// The program length is not a multiple of the values printed per iteration.
+    UnalignedOutput {
+        length: usize,
+        outputs_per_iteration: usize,
+    },
+    /// A would need more than 64 bits.
+    TooLarge,
+    /// A combo operand of 7 was found at this address.
+    InvalidOperand(usize),
+    /// The search finished without finding a value.
+    NoSolution,
+}
+
+impl Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoFinalJump => write!(f, "the program does not end with `jnz 0`"),
+            Self::InnerJump(pc) => write!(f, "jump at {pc} is not the final `jnz 0`"),
+            Self::VariableShift(pc) => {
+                write!(f, "A is shifted by a register at {pc}, not a constant")
+            }
+            Self::NoShift => write!(f, "A is never shifted, the loop cannot end"),
+            Self::CarriedRegister(r) => {
+                write!(f, "register {r} is read before being written in the loop")
+            }
+            Self::NoOutput => write!(f, "the loop prints nothing"),
+            Self::UnalignedOutput {
+                length,
+                outputs_per_iteration,
+            } => write!(
+                f,
+                "the program has {length} values but each iteration prints {outputs_per_iteration}"
+            ),
+            Self::TooLarge => write!(f, "A would need more than 64 bits"),
+            Self::InvalidOperand(pc) => write!(f, "invalid combo operand 7 at {pc}"),
+            Self::NoSolution => write!(f, "no value of A makes the program print itself"),
+        }
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// Checks that `program` is a single loop that shifts A right by a constant each iteration and
+/// otherwise only uses registers it wrote itself, then measures how its output depends on A.
+pub fn analyse(program: &Program) -> Result<Analysis, Unsupported> {
+    let body: Vec<(usize, Instruction)> = program
+        .code
+        .chunks_exact(2)
+        .enumerate()
+        .filter_map(|(i, chunk)| Some((2 * i, Instruction::decode(chunk[0], chunk[1])?)))
+        .collect();
+    match body.last() {
+        Some((_, Instruction::Jnz(0))) if program.code.len().is_multiple_of(2) => {}
+        _ => return Err(Unsupported::NoFinalJump),
+    }
+
+    let mut shift = 0;
+    let mut shift_pcs = vec![];
+    let mut outputs_per_iteration = 0;
+    let mut written = [false; 3];
+    for &(pc, instruction) in &body[..body.len() - 1] {
+        let mut reads = vec![];
+        if let Operand::Combo(n) = instruction.operand_kind() {
+            match n {
+                5 => reads.push(1),
+                6 => reads.push(2),
+                7 => return Err(Unsupported::InvalidOperand(pc)),
+                _ => {}
+            }
+        }
+        match instruction {
+            Instruction::Adv(n) if n <= 3 => {
+                shift += n as u32;
+                shift_pcs.push(pc);
+            }
+            Instruction::Adv(_) => return Err(Unsupported::VariableShift(pc)),
+            Instruction::Jnz(_) => return Err(Unsupported::InnerJump(pc)),
+            Instruction::Bxl(_) => reads.push(1),
+            Instruction::Bxc(_) => reads.extend([1, 2]),
+            Instruction::Out(_) => outputs_per_iteration += 1,
+            _ => {}
+        }
+        if let Some(&register) = reads.iter().find(|&&r| !written[r]) {
+            return Err(Unsupported::CarriedRegister(
+                (b'A' + register as u8) as char,
+            ));
+        }
+        match instruction {
+            Instruction::Bxl(_)
+            | Instruction::Bst(_)
+            | Instruction::Bxc(_)
+            | Instruction::Bdv(_) => written[1] = true,
+            Instruction::Cdv(_) => written[2] = true,
+            _ => {}
+        }
+    }
+
+    if shift == 0 {
+        return Err(Unsupported::NoShift);
+    }
+    if outputs_per_iteration == 0 {
+        return Err(Unsupported::NoOutput);
+    }
+    if !program.code.len().is_multiple_of(outputs_per_iteration) {
+        return Err(Unsupported::UnalignedOutput {
+            length: program.code.len(),
+            outputs_per_iteration,
+        });
+    }
+    let iterations = program.code.len() / outputs_per_iteration;
+    if shift as usize * iterations > 64 {
+        return Err(Unsupported::TooLarge);
+    }
+
+    Ok(Analysis {
+        shift,
+        shift_pcs,
+        outputs_per_iteration,
+        dependency_bits: dependency_bits(program),
+    })
+}
+
+/// Flips each bit of A for a few sample values and returns one past the highest bit that
+/// changed the first output.
+fn dependency_bits(program: &Program) -> u32 {
+    let mut sample: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut bits = 0;
+    for _ in 0..16 {
+        // xorshift, for reproducible pseudo-random samples
+        sample ^= sample << 13;
+        sample ^= sample >> 7;
+        sample ^= sample << 17;
+        let base = first_output(program, sample);
+        if let Some(bit) =
+            (bits..64).rfind(|&bit| first_output(program, sample ^ (1 << bit)) != base)
+        {
+            bits = bit + 1;
+        }
+    }
+    bits
+}
+
+fn first_output(program: &Program, a: u64) -> Option<u8> {
+    let mut machine = Machine::new(&Program {
+        registers: Registers {
+            a,
+            ..program.registers
+        },
+        code: program.code.clone(),
+    })
+    .with_step_limit(program.code.len());
+    while machine.step().ok()?.output.is_none() {}
+    machine.output().first().copied()
+}
+
+/// Finds the smallest positive value of A for which `program` prints its own code.
+///
+/// Iterations are solved from the last one, whose A only holds the most significant bits, to
+/// the first one. At each level the candidates for the next lower bits are tried in increasing
+/// order and kept only if running the program prints the expected end of the code, so the first
+/// full solution is the smallest.
+pub fn find_quine(program: &Program) -> Result<u64, Unsupported> {
+    let analysis = analyse(program)?;
+    let iterations = program.code.len() / analysis.outputs_per_iteration;
+    let step_limit = (iterations + 1) * program.code.len();
+    search(program, &analysis, 0, 1, iterations, step_limit).ok_or(Unsupported::NoSolution)
+}
+
+fn search(
+    program: &Program,
+    analysis: &Analysis,
+    high_bits: u64,
+    level: usize,
+    iterations: usize,
+    step_limit: usize,
+) -> Option<u64> {
+    let expected = &program.code[program.code.len() - level * analysis.outputs_per_iteration..];
+    (0..1u64 << analysis.shift).find_map(|low_bits| {
+        let a = high_bits << analysis.shift | low_bits;
+        if a == 0 && level == iterations {
+            return None;
+        }
+        if program.run_with_a(a, Some(step_limit)).ok()? != expected {
+            return None;
+        }
+        if level == iterations {
+            Some(a)
+        } else {
+            search(program, analysis, a, level + 1, iterations, step_limit)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyse, find_quine, Unsupported};
+    use crate::chronospatial::{assemble, Program, Registers};
+
+    fn program(text: &str) -> Program {
+        Program {
+            registers: Registers::default(),
+            code: assemble(text).unwrap(),
+        }
+    }
+
+    #[test]
+    fn solves_puzzle_example() {
+        let example = program("adv 3\nout A\njnz 0");
+        let analysis = analyse(&example).unwrap();
+        assert_eq!(analysis.shift, 3);
+        assert_eq!(analysis.shift_pcs, [0]);
+        assert_eq!(analysis.dependency_bits, 6);
+        assert_eq!(find_quine(&example), Ok(117440));
+    }
+
+    #[test]
+    fn solves_programs_mixing_high_bits() {
+        // Typical puzzle input shape: the output depends on up to 10 bits of A.
+        let quine = program("bst A\nbxl 1\ncdv B\nbxc 4\nbxl 4\nadv 3\nout B\njnz 0");
+        assert_eq!(analyse(&quine).unwrap().dependency_bits, 10);
+        let a = find_quine(&quine).unwrap();
+        assert_eq!(quine.run_with_a(a, None), Ok(quine.code.clone()));
+    }
+
+    #[test]
+    fn explains_unsupported_programs() {
+        assert_eq!(
+            find_quine(&program("out A\nadv 1")),
+            Err(Unsupported::NoFinalJump)
+        );
+        assert_eq!(
+            find_quine(&program("adv B\nout A\njnz 0")),
+            Err(Unsupported::VariableShift(0))
+        );
+        assert_eq!(
+            find_quine(&program("bxl 1\nadv 1\nout B\njnz 0")),
+            Err(Unsupported::CarriedRegister('B'))
+        );
+        assert_eq!(
+            find_quine(&program("out A\njnz 0")),
+            Err(Unsupported::NoShift)
+        );
+        assert_eq!(
+            find_quine(&program("adv 1\nout 1\njnz 0")),
+            Err(Unsupported::NoSolution)
+        );
+        assert_eq!(
+            Unsupported::CarriedRegister('B').to_string(),
+            "register B is read before being written in the loop"
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! The amphipod disk compaction from day 9.
+//!
+//! Everything works on the runs of the disk map, never on single blocks, so disk maps of
+//! millions of digits compact in linear time.
+
+/// Largest run length a digit of the disk map can give.
+const MAX_RUN: usize = 9;
+
+/// Run of blocks starting at block `start`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: u64,
+    pub len: u64,
+}
+
+/// Blocks of file `id`, as laid out on the disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub id: usize,
+    pub start: u64,
+    pub len: u64,
+}
+
+impl Segment {
+    /// Sum of `position * id` over the blocks of the segment.
+    pub fn checksum(&self) -> u128 {
+        let (start, len) = (u128::from(self.start), u128::from(self.len));
+        let positions = start * len + len * len.saturating_sub(1) / 2;
+        self.id as u128 * positions
+    }
+}
+
+/// The disk map: file `i` is followed by gap `i`, the last file by no gap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disk {
+    pub files: Vec<Span>,
+    pub gaps: Vec<Span>,
+}
+
+pub fn parse(input: &str) -> Disk {
+    let mut disk = Disk {
+        files: vec![],
+        gaps: vec![],
+    };
+    let mut start = 0;
+    for (i, digit) in input.trim_end().bytes().enumerate() {
+        assert!(digit.is_ascii_digit(), "disk map digit");
+        let span = Span {
+            start,
+            len: u64::from(digit - b'0'),
+        };
+        start += span.len;
+        if i % 2 == 0 {
+            disk.files.push(span);
+        } else {
+            disk.gaps.push(span);
+        }
+    }
+    disk
+}
+
+/// Sum of the checksums of the segments.
+pub fn checksum(segments: &[Segment]) -> u128 {
+    segments.iter().map(Segment::checksum).sum()
+}
+
+impl Disk {
+    /// Number of blocks on the disk.
+    pub fn size(&self) -> u64 {
+        let last = |spans: &[Span]| spans.last().map_or(0, |s| s.start + s.len);
+        last(&self.files).max(last(&self.gaps))
+    }
+
+    /// The files where they are before compaction.
+    pub fn layout(&self) -> Vec<Segment> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(id, span)| Segment {
+                id,
+                start: span.start,
+                len: span.len,
+            })
+            .collect()
+    }
+
+    /// Part one: moves blocks one at a time from the end of the disk to the leftmost free block.
+    ///
+    /// Walks the runs from both ends at once. Each file from the front stays in place and the gap
+    /// after it is filled with as much of the last files as fits, splitting the file at the
+    /// boundary, so every run is visited once.
+    pub fn compact_blocks(&self) -> Vec<Segment> {
+        let mut segments = vec![];
+        let Some(mut back) = self.files.len().checked_sub(1) else {
+            return segments;
+        };
+        let mut back_left = self.files[back].len;
+        let mut position = 0;
+        for (id, file) in self.files.iter().enumerate() {
+            if id > back {
+                break;
+            }
+            let len = if id == back { back_left } else { file.len };
+            segments.push(Segment {
+                id,
+                start: position,
+                len,
+            });
+            position += len;
+            if id == back {
+                break;
+            }
+            let mut free = self.gaps[id].len;
+            while free > 0 && back > id {
+                let moved = free.min(back_left);
+                if moved > 0 {
+                    segments.push(Segment {
+                        id: back,
+                        start: position,
+                        len: moved,
+                    });
+                }
+                position += moved;
+                free -= moved;
+                back_left -= moved;
+                if back_left == 0 {
+                    back -= 1;
+                    back_left = self.files[back].len;
+                }
+            }
+        }
+        segments.retain(|segment| segment.len > 0);
+        segments
+    }
+
+    /// Part two: moves whole files, from the highest id down, to the leftmost gap they fit in.
+    ///
+    /// Gaps only shrink, so a gap too small for a file size stays too small for good. For each
+    /// size the leftmost gap that could still take it is kept and only ever moves right, which
+    /// makes the whole pass linear in the number of runs.
+    pub fn compact_files(&self) -> Vec<Segment> {
+        // Gaps on both sides of an empty file make a single free span.
+        let mut free: Vec<Span> = vec![];
+        for gap in self.gaps.iter().filter(|gap| gap.len > 0) {
+            match free.last_mut() {
+                Some(last) if last.start + last.len == gap.start => last.len += gap.len,
+                _ => free.push(*gap),
+            }
+        }
+        let mut first_fit = [0; MAX_RUN + 1];
+        let mut segments = Vec::with_capacity(self.files.len());
+        for (id, file) in self.files.iter().enumerate().rev() {
+            let before_file = |fit: usize| fit < free.len() && free[fit].start < file.start;
+            let fit = &mut first_fit[(file.len as usize).min(MAX_RUN)];
+            while before_file(*fit) && free[*fit].len < file.len {
+                *fit += 1;
+            }
+            let start = if before_file(*fit) {
+                let gap = &mut free[*fit];
+                let start = gap.start;
+                gap.start += file.len;
+                gap.len -= file.len;
+                start
+            } else {
+                file.start
+            };
+            segments.push(Segment {
+                id,
+                start,
+                len: file.len,
+            });
+        }
+        segments.sort_by_key(|segment| segment.start);
+        segments
+    }
+
+    /// The blocks as drawn in the puzzle text, each file by the last digit of its id and free
+    /// blocks as `.`. Meant for the small examples.
+    pub fn render(&self, segments: &[Segment]) -> String {
+        let mut blocks = vec!['.'; self.size() as usize];
+        for segment in segments {
+            let digit = char::from_digit((segment.id % 10) as u32, 10).expect("decimal digit");
+            let start = segment.start as usize;
+            blocks[start..start + segment.len as usize].fill(digit);
+        }
+        blocks.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checksum, parse, Disk};
+
+    const EXAMPLE: &str = "2333133121414131402\n";
+
+    /// Block by block compaction, as the puzzle describes it.
+    fn naive(disk: &Disk, whole_files: bool) -> u128 {
+        let mut blocks = vec![None; disk.size() as usize];
+        for (id, file) in disk.files.iter().enumerate() {
+            for p in file.start..file.start + file.len {
+                blocks[p as usize] = Some(id);
+            }
+        }
+        if whole_files {
+            for (id, file) in disk.files.iter().enumerate().rev() {
+                let (start, len) = (file.start as usize, file.len as usize);
+                let target = (0..start).find(|&p| blocks[p..p + len].iter().all(Option::is_none));
+                if let Some(target) = target {
+                    blocks[start..start + len].fill(None);
+                    blocks[target..target + len].fill(Some(id));
+                }
+            }
+        } else if !blocks.is_empty() {
+            let (mut front, mut back) = (0, blocks.len() - 1);
+            while front < back {
+                match (blocks[front], blocks[back]) {
+                    (Some(_), _) => front += 1,
+                    (_, None) => back -= 1,
+                    _ => blocks.swap(front, back),
+                }
+            }
+        }
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(p, id)| (p * id.unwrap_or(0)) as u128)
+            .sum()
+    }
+
+    #[test]
+    fn renders_example() {
+        let disk = parse(EXAMPLE);
+        assert_eq!(
+            disk.render(&disk.layout()),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        let blocks = disk.compact_blocks();
+        assert_eq!(
+            disk.render(&blocks),
+            "0099811188827773336446555566.............."
+        );
+        assert_eq!(checksum(&blocks), 1928);
+        let files = disk.compact_files();
+        assert_eq!(
+            disk.render(&files),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+        assert_eq!(checksum(&files), 2858);
+    }
+
+    #[test]
+    fn matches_naive_compaction() {
+        // Small pseudo-random disk maps, zero lengths included.
+        let mut state = 2024u64;
+        for _ in 0..200 {
+            let len = 1 + (state % 40) as usize;
+            let map: String = (0..len)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    char::from(b'0' + (state >> 60) as u8 % 10)
+                })
+                .collect();
+            let disk = parse(&map);
+            assert_eq!(
+                checksum(&disk.compact_blocks()),
+                naive(&disk, false),
+                "{map}"
+            );
+            assert_eq!(checksum(&disk.compact_files()), naive(&disk, true), "{map}");
+        }
+    }
+
+    #[test]
+    fn compacts_millions_of_digits() {
+        let map: String = (0..2_000_001u64)
+            .map(|i| char::from(b'1' + ((i * 7 + i / 3) % 9) as u8))
+            .collect();
+        let disk = parse(&map);
+        let used: u64 = disk.files.iter().map(|file| file.len).sum();
+
+        // Part one leaves the used blocks packed at the start of the disk.
+        let blocks = disk.compact_blocks();
+        assert!(blocks
+            .windows(2)
+            .all(|w| w[0].start + w[0].len == w[1].start));
+        assert_eq!(blocks.last().map(|s| s.start + s.len), Some(used));
+
+        // Part two keeps every file whole, never overlapping another one.
+        let files = disk.compact_files();
+        assert_eq!(files.len(), disk.files.len());
+        assert!(files
+            .windows(2)
+            .all(|w| w[0].start + w[0].len <= w[1].start));
+        assert!(checksum(&files) < checksum(&disk.layout()));
+    }
+}
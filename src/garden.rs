@@ -0,0 +1,192 @@
+//! The garden plots from day 12.
+use std::collections::BTreeSet;
+
+use crate::grid::Grid;
+use crate::region::Region;
+use crate::template::{ansi_background, ANSI_BLACK, ANSI_RESET};
+
+/// Background colours from the 256 colour palette, chosen to be told apart side by side.
+const PALETTE: [u8; 12] = [196, 208, 226, 46, 51, 21, 201, 94, 30, 99, 160, 142];
+
+/// Connected area growing a single plant.
+#[derive(Clone, Debug)]
+pub struct Plot {
+    pub plant: char,
+    pub region: Region,
+}
+
+impl Plot {
+    /// Fence price of part one, area times perimeter.
+    pub fn price(&self) -> usize {
+        self.region.area() * self.region.perimeter()
+    }
+
+    /// Bulk discount price of part two, area times number of sides.
+    pub fn discounted_price(&self) -> usize {
+        self.region.area() * self.region.sides()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Garden {
+    pub map: Grid<char>,
+    /// Plots in row-major order of their first cell.
+    pub plots: Vec<Plot>,
+    /// Index in `plots` of the plot each cell belongs to.
+    pub labels: Grid<usize>,
+    /// Indices of the plots sharing an edge with each plot.
+    pub adjacent: Vec<BTreeSet<usize>>,
+}
+
+pub fn parse(input: &str) -> Garden {
+    let map = Grid::parse(input);
+    let components = map.components(|a, b| a == b);
+    let adjacent = components.adjacency();
+    let plots = components
+        .cells
+        .into_iter()
+        .map(|cells| Plot {
+            plant: map[cells[0]],
+            region: Region::new(cells),
+        })
+        .collect();
+    Garden {
+        map,
+        plots,
+        labels: components.labels,
+        adjacent,
+    }
+}
+
+impl Garden {
+    /// Index in [`PALETTE`] of the colour of each plot, different for plots side by side.
+    ///
+    /// Plots are coloured greedily in smallest-last order: the plot with the fewest neighbours
+    /// is set aside, the rest coloured first, and it then takes the first colour its neighbours
+    /// left. The plots of a map form a planar graph, where some plot always has at most five
+    /// neighbours, so six colours are enough.
+    pub fn colours(&self) -> Vec<usize> {
+        let n = self.plots.len();
+        let mut degree: Vec<usize> = self.adjacent.iter().map(BTreeSet::len).collect();
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        for _ in 0..n {
+            let plot = (0..n)
+                .filter(|&i| !removed[i])
+                .min_by_key(|&i| degree[i])
+                .expect("plot left");
+            removed[plot] = true;
+            order.push(plot);
+            for &other in &self.adjacent[plot] {
+                degree[other] -= 1;
+            }
+        }
+
+        let mut colours: Vec<Option<usize>> = vec![None; n];
+        for &plot in order.iter().rev() {
+            let taken: BTreeSet<usize> = self.adjacent[plot]
+                .iter()
+                .filter_map(|&other| colours[other])
+                .collect();
+            colours[plot] = (0..PALETTE.len()).find(|c| !taken.contains(c));
+        }
+        colours
+            .into_iter()
+            .map(|colour| colour.expect("six colours suffice on a planar map"))
+            .collect()
+    }
+
+    /// The map with each plot on its own background colour, so that neighbouring plots, of the
+    /// same plant or not, stand out.
+    pub fn render(&self) -> String {
+        let colours = self.colours();
+        let mut out = String::new();
+        for (row, labels) in self.map.rows().zip(self.labels.rows()) {
+            for (&plant, &label) in row.iter().zip(labels) {
+                let background = ansi_background(PALETTE[colours[label]]);
+                out += &format!("{background}{ANSI_BLACK}{plant}{ANSI_RESET}");
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// One line per plot with its measures and both prices, then the totals.
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "{:>4} {:>5} {:>5} {:>9} {:>5} {:>7} {:>10}\n",
+            "id", "plant", "area", "perimeter", "sides", "price", "discounted"
+        );
+        for (id, plot) in self.plots.iter().enumerate() {
+            out += &format!(
+                "{id:>4} {:>5} {:>5} {:>9} {:>5} {:>7} {:>10}\n",
+                plot.plant,
+                plot.region.area(),
+                plot.region.perimeter(),
+                plot.region.sides(),
+                plot.price(),
+                plot.discounted_price()
+            );
+        }
+        out += &format!(
+            "{:>4} {:>5} {:>5} {:>9} {:>5} {:>7} {:>10}\n",
+            "",
+            "",
+            "",
+            "",
+            "total",
+            self.plots.iter().map(Plot::price).sum::<usize>(),
+            self.plots.iter().map(Plot::discounted_price).sum::<usize>()
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn reports_each_plot() {
+        let garden = parse("AAAA\nBBCD\nBBCC\nEEEC\n");
+        assert_eq!(
+            garden.report(),
+            "  id plant  area perimeter sides   price discounted
+   0     A     4        10     4      40         16
+   1     B     4         8     4      32         16
+   2     C     4        10     8      40         32
+   3     D     1         4     4       4          4
+   4     E     3         8     4      24         12
+                           total     140         80
+"
+        );
+    }
+
+    #[test]
+    fn colours_neighbouring_plots_apart() {
+        let garden = parse("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO\n");
+        assert_eq!(garden.plots.len(), 5);
+        let picture = garden.render();
+        assert_eq!(picture.lines().count(), 5);
+        assert!(picture.starts_with("\x1b[48;5;208m\x1b[30mO\x1b[0m"));
+        assert!(picture.contains("\x1b[48;5;196m\x1b[30mX\x1b[0m"));
+
+        // Hundreds of small plots, where plot ids alone would repeat colours side by side.
+        let map: String = (0..40)
+            .map(|y| {
+                let row: String = (0..40)
+                    .map(|x| char::from(b'A' + ((x * 7 + y * 3 + x * y) % 5) as u8))
+                    .collect();
+                row + "\n"
+            })
+            .collect();
+        let garden = parse(&map);
+        assert!(garden.plots.len() > 100);
+        let colours = garden.colours();
+        for (plot, adjacent) in garden.adjacent.iter().enumerate() {
+            for &other in adjacent {
+                assert_ne!(colours[plot], colours[other]);
+            }
+        }
+    }
+}
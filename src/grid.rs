@@ -0,0 +1,277 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::union_find::UnionFind;
+
+/// A cell coordinate as `(x, y)`, with `x` the column and `y` the row.
+pub type Point = (usize, usize);
+
+/// Offsets of the four orthogonal neighbours, in up, right, down, left order.
+pub const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Rectangular grid stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// Connected components of a grid, see [`Grid::components`].
+#[derive(Clone, Debug)]
+pub struct Components {
+    /// Label of every cell. Labels are numbered in row-major order of their first cell.
+    pub labels: Grid<usize>,
+    /// Cells of each label, in row-major order.
+    pub cells: Vec<Vec<Point>>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of `width` by `height` cells, all set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Creates a grid from its cells in row-major order.
+    ///
+    /// # Panics
+    /// Panics if `cells` does not contain exactly `width * height` values.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "grid size mismatch");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.in_bounds(point)
+            .then(|| &self.cells[self.index_of(point)])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if !self.in_bounds(point) {
+            return None;
+        }
+        let index = self.index_of(point);
+        Some(&mut self.cells[index])
+    }
+
+    /// Row-major index of `point`, usable as a dense id e.g. for [`UnionFind`].
+    pub fn index_of(&self, (x, y): Point) -> usize {
+        y * self.width + x
+    }
+
+    /// Inverse of [`Grid::index_of`].
+    pub fn point_of(&self, index: usize) -> Point {
+        (index % self.width, index / self.width)
+    }
+
+    /// Moves `point` by `(dx, dy)`, returns `None` when leaving the grid.
+    pub fn step(&self, (x, y): Point, (dx, dy): (isize, isize)) -> Option<Point> {
+        let next = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.in_bounds(next).then_some(next)
+    }
+
+    /// Orthogonal neighbours of `point` that lie inside the grid.
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS
+            .iter()
+            .filter_map(move |&direction| self.step(point, direction))
+    }
+
+    /// All coordinates in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| (i % width, i / width))
+    }
+
+    /// All cells with their coordinates, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Labels the groups of orthogonally adjacent cells for which `connected` holds.
+    pub fn components(&self, connected: impl Fn(&T, &T) -> bool) -> Components {
+        let mut union_find = UnionFind::new(self.cells.len());
+        for (point, value) in self.iter() {
+            for direction in [(1, 0), (0, 1)] {
+                if let Some(next) = self.step(point, direction) {
+                    if connected(value, &self[next]) {
+                        union_find.union(self.index_of(point), self.index_of(next));
+                    }
+                }
+            }
+        }
+
+        let mut label_of_root = vec![usize::MAX; self.cells.len()];
+        let mut cells: Vec<Vec<Point>> = vec![];
+        let labels = (0..self.cells.len())
+            .map(|index| {
+                let root = union_find.find(index);
+                if label_of_root[root] == usize::MAX {
+                    label_of_root[root] = cells.len();
+                    cells.push(vec![]);
+                }
+                cells[label_of_root[root]].push(self.point_of(index));
+                label_of_root[root]
+            })
+            .collect();
+
+        Components {
+            labels: Grid::from_vec(self.width, self.height, labels),
+            cells,
+        }
+    }
+}
+
+impl Grid<char> {
+    /// Parses a block of text, one row per line.
+    ///
+    /// # Panics
+    /// Panics if the lines are not all of the same length.
+    pub fn parse(input: &str) -> Self {
+        let rows: Vec<Vec<char>> = input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect())
+            .collect();
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "rows must have the same length"
+        );
+        Self::from_vec(width, height, rows.into_iter().flatten().collect())
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        assert!(self.in_bounds(point), "{point:?} is outside of the grid");
+        &self.cells[self.index_of(point)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        assert!(self.in_bounds(point), "{point:?} is outside of the grid");
+        let index = self.index_of(point);
+        &mut self.cells[index]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Components {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Labels of the components sharing an edge with each component.
+    pub fn adjacency(&self) -> Vec<BTreeSet<usize>> {
+        let mut adjacent = vec![BTreeSet::new(); self.len()];
+        for (point, &label) in self.labels.iter() {
+            for neighbour in self.labels.neighbours(point) {
+                if self.labels[neighbour] != label {
+                    adjacent[label].insert(self.labels[neighbour]);
+                }
+            }
+        }
+        adjacent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn parses_and_displays() {
+        let grid = Grid::parse("ab\ncd\nef\n");
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+    }
+
+    #[test]
+    fn lists_neighbours_inside_grid() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            grid.neighbours((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours((1, 1)).count(), 4);
+    }
+
+    #[test]
+    fn labels_components() {
+        let grid = Grid::parse("AAB\nBAB\nBBB\n");
+        let components = grid.components(|a, b| a == b);
+        assert_eq!(components.len(), 2);
+        assert_eq!(components.cells[0], [(0, 0), (1, 0), (1, 1)]);
+        assert_eq!(components.cells[1].len(), 6);
+        assert_eq!(components.labels[(0, 1)], 1);
+    }
+
+    #[test]
+    fn separates_same_value_regions() {
+        let grid = Grid::parse("ABA\n");
+        let components = grid.components(|a, b| a == b);
+        assert_eq!(components.len(), 3);
+        assert_eq!(components.labels.to_string(), "012\n");
+        let adjacency = components.adjacency();
+        assert_eq!(adjacency[0].iter().collect::<Vec<_>>(), [&1]);
+        assert_eq!(adjacency[1].iter().collect::<Vec<_>>(), [&0, &2]);
+    }
+}
@@ -1,3 +1,24 @@
 pub mod template;
 
 // Use this file to add helper functions and additional modules.
+pub mod antennas;
+pub mod chronospatial;
+pub mod disk;
+pub mod garden;
+pub mod grid;
+pub mod maze;
+pub mod memo;
+pub mod mull;
+pub mod print_queue;
+pub mod ram_run;
+pub mod region;
+pub mod replay;
+pub mod reports;
+pub mod restroom;
+pub mod stones;
+pub mod toposort;
+pub mod towels;
+pub mod trails;
+pub mod union_find;
+pub mod warehouse;
+pub mod word_search;
@@ -1,4 +1,7 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, antennas, bytes, disk, download, garden, maze, mull, read, replay, reports, scaffold,
+    solve, step, time,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,6 +10,7 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
+    use advent_of_code::antennas::Harmonics;
     use advent_of_code::template::Day;
     use std::process;
 
@@ -31,6 +35,43 @@ mod args {
         All {
             release: bool,
         },
+        Antennas {
+            path: String,
+            harmonics: Harmonics,
+        },
+        Bytes {
+            path: String,
+        },
+        Disk {
+            path: String,
+            whole_files: bool,
+        },
+        Garden {
+            path: String,
+        },
+        Maze {
+            path: String,
+            svg: Option<String>,
+        },
+        Mull {
+            path: String,
+            all: bool,
+            debug: bool,
+        },
+        Replay {
+            path: String,
+            wide: bool,
+            delay: u64,
+            dump: Option<String>,
+            frames: Option<String>,
+        },
+        Reports {
+            path: String,
+            explain: bool,
+        },
+        Step {
+            path: String,
+        },
         Time {
             all: bool,
             day: Option<Day>,
@@ -47,6 +88,45 @@ mod args {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
             },
+            Some("antennas") => AppArguments::Antennas {
+                harmonics: args
+                    .opt_value_from_str("--harmonics")?
+                    .unwrap_or(Harmonics::PART_ONE),
+                path: args.free_from_str()?,
+            },
+            Some("bytes") => AppArguments::Bytes {
+                path: args.free_from_str()?,
+            },
+            Some("disk") => AppArguments::Disk {
+                whole_files: args.contains("--whole-files"),
+                path: args.free_from_str()?,
+            },
+            Some("garden") => AppArguments::Garden {
+                path: args.free_from_str()?,
+            },
+            Some("maze") => AppArguments::Maze {
+                svg: args.opt_value_from_str("--svg")?,
+                path: args.free_from_str()?,
+            },
+            Some("mull") => AppArguments::Mull {
+                all: args.contains("--all"),
+                debug: args.contains("--debug"),
+                path: args.free_from_str()?,
+            },
+            Some("replay") => AppArguments::Replay {
+                wide: args.contains("--wide"),
+                delay: args.opt_value_from_str("--delay")?.unwrap_or(100),
+                dump: args.opt_value_from_str("--dump")?,
+                frames: args.opt_value_from_str("--frames")?,
+                path: args.free_from_str()?,
+            },
+            Some("reports") => AppArguments::Reports {
+                explain: args.contains("--explain"),
+                path: args.free_from_str()?,
+            },
+            Some("step") => AppArguments::Step {
+                path: args.free_from_str()?,
+            },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
@@ -104,6 +184,21 @@ fn main() {
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
             AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::Antennas { path, harmonics } => antennas::handle(&path, harmonics),
+            AppArguments::Bytes { path } => bytes::handle(&path),
+            AppArguments::Disk { path, whole_files } => disk::handle(&path, whole_files),
+            AppArguments::Garden { path } => garden::handle(&path),
+            AppArguments::Maze { path, svg } => maze::handle(&path, svg.as_deref()),
+            AppArguments::Mull { path, all, debug } => mull::handle(&path, all, debug),
+            AppArguments::Replay {
+                path,
+                wide,
+                delay,
+                dump,
+                frames,
+            } => replay::handle(&path, wide, delay, dump.as_deref(), frames.as_deref()),
+            AppArguments::Reports { path, explain } => reports::handle(&path, explain),
+            AppArguments::Step { path } => step::handle(&path),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
@@ -0,0 +1,261 @@
+//! The reindeer maze from day 16.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::grid::{Grid, Point};
+use crate::template::{ANSI_BOLD, ANSI_GREEN, ANSI_RESET, ANSI_YELLOW};
+
+/// Side of a maze tile in SVG pixels.
+const SVG_TILE: usize = 10;
+
+const MOVE_COST: usize = 1;
+const TURN_COST: usize = 1000;
+
+pub struct Maze {
+    pub map: Grid<char>,
+    pub start: Point,
+    pub end: Point,
+}
+
+/// Every tile lying on at least one path of the lowest score.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BestPaths {
+    pub score: usize,
+    pub tiles: HashSet<Point>,
+    /// Tiles where at least one best path turns.
+    pub turns: HashSet<Point>,
+}
+
+pub fn parse(input: &str) -> Maze {
+    let map = Grid::parse(input);
+    let find = |target| {
+        map.iter()
+            .find(|(_, &c)| c == target)
+            .map(|(point, _)| point)
+    };
+    let start = find('S').expect("start tile");
+    let end = find('E').expect("end tile");
+    Maze { map, start, end }
+}
+
+/// Directions as `(row, column)` offsets, starting east and turning counterclockwise.
+const ROW_DIRECTIONS: [(i32, i32); 4] = [(0, 1), (-1, 0), (0, -1), (1, 0)];
+
+/// A tile in `(row, column)` coordinates.
+type Tile = (i32, i32);
+
+/// A tile with the score of a path reaching it.
+type Scored = (i32, i32, usize);
+
+/// Dijkstra's algorithm over positions and directions, in `(row, column)` coordinates. Each step
+/// moves one tile, turning first if needed, and `prev` keeps every predecessor reaching a tile
+/// with a given score. Walking them back from the goal gives the tiles on best paths, and the
+/// steps that turned give the turn points.
+fn dijkstra(user: Tile, goal: Tile, map: &[Vec<char>]) -> Option<BestPaths> {
+    let height = map.len() as i32;
+    let width = map[0].len() as i32;
+    let mut seen: HashMap<(Tile, usize), usize> = HashMap::new();
+    let mut heap: BinaryHeap<Reverse<(usize, Tile, usize)>> = BinaryHeap::new();
+    heap.push(Reverse((0, user, 0)));
+    seen.entry((user, 0)).or_insert(0);
+
+    let mut prev: HashMap<Scored, Vec<Scored>> = HashMap::new();
+    let mut solution_score: Option<usize> = None;
+
+    while let Some(Reverse((score, (x, y), dir))) = heap.pop() {
+        if (x, y) == goal {
+            solution_score = Some(score);
+            continue;
+        }
+        if score > solution_score.unwrap_or(usize::MAX) {
+            break;
+        }
+        for (d, (dx, dy)) in ROW_DIRECTIONS.iter().enumerate() {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= height || ny >= width {
+                continue;
+            }
+            let c = map[nx as usize][ny as usize];
+            if c == '#' {
+                continue;
+            }
+            let new_score = match (dir, d) {
+                (ddir, dd) if ddir % 2 != dd % 2 => score + TURN_COST + MOVE_COST,
+                (ddir, dd) if ddir == dd => score + MOVE_COST,
+                _ => continue,
+            };
+            if let Some(old_score) = seen.get(&((nx, ny), d)) {
+                if new_score == *old_score {
+                    prev.entry((nx, ny, new_score))
+                        .or_default()
+                        .push((x, y, score));
+                }
+                continue;
+            }
+            seen.entry(((nx, ny), d)).or_insert(new_score);
+
+            heap.push(Reverse((new_score, (nx, ny), d)));
+            prev.entry((nx, ny, new_score))
+                .or_default()
+                .push((x, y, score));
+        }
+    }
+    let solution_score = solution_score?;
+    let mut positions: HashSet<Tile> = HashSet::new();
+    let mut turns: HashSet<Tile> = HashSet::new();
+
+    let mut queue: VecDeque<Scored> = VecDeque::new();
+    queue.push_back((goal.0, goal.1, solution_score));
+    while let Some((x, y, score)) = queue.pop_front() {
+        positions.insert((x, y));
+        if let Some(before) = prev.get(&(x, y, score)) {
+            for &(nx, ny, ns) in before {
+                if score - ns > MOVE_COST {
+                    turns.insert((nx, ny));
+                }
+                queue.push_back((nx, ny, ns));
+            }
+        }
+    }
+
+    let to_point = |(x, y): &Tile| (*y as usize, *x as usize);
+    Some(BestPaths {
+        score: solution_score,
+        tiles: positions.iter().map(to_point).collect(),
+        turns: turns.iter().map(to_point).collect(),
+    })
+}
+
+impl Maze {
+    pub fn best_paths(&self) -> Option<BestPaths> {
+        let map: Vec<Vec<char>> = self.map.rows().map(|row| row.to_vec()).collect();
+        let row_column = |(x, y): Point| (y as i32, x as i32);
+        dijkstra(row_column(self.start), row_column(self.end), &map)
+    }
+
+    /// The maze with best path tiles as `O` and turn points as `+`, in colour when `ansi` is set.
+    pub fn render(&self, paths: &BestPaths, ansi: bool) -> String {
+        let mut out = String::new();
+        for y in 0..self.map.height() {
+            for x in 0..self.map.width() {
+                let c = self.map[(x, y)];
+                let (c, colour) = if c == 'S' || c == 'E' {
+                    (c, ANSI_BOLD)
+                } else if paths.turns.contains(&(x, y)) {
+                    ('+', ANSI_YELLOW)
+                } else if paths.tiles.contains(&(x, y)) {
+                    ('O', ANSI_GREEN)
+                } else {
+                    (c, "")
+                };
+                if ansi && !colour.is_empty() {
+                    out += &format!("{colour}{c}{ANSI_RESET}");
+                } else {
+                    out.push(c);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// SVG picture of the maze, with best path tiles in green and turn points as orange dots.
+    pub fn svg(&self, paths: &BestPaths) -> String {
+        let (width, height) = (self.map.width() * SVG_TILE, self.map.height() * SVG_TILE);
+        let rect = |(x, y): Point, fill: &str| {
+            format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{SVG_TILE}\" height=\"{SVG_TILE}\" fill=\"{fill}\"/>\n",
+                x * SVG_TILE,
+                y * SVG_TILE
+            )
+        };
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        svg += &format!("  <rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>\n");
+        for (point, &c) in self.map.iter() {
+            if c == '#' {
+                svg += &rect(point, "#333");
+            } else if paths.tiles.contains(&point) {
+                svg += &rect(point, "#4c4");
+            }
+        }
+        let mut turns: Vec<&Point> = paths.turns.iter().collect();
+        turns.sort_unstable_by_key(|&&(x, y)| (y, x));
+        for &(x, y) in turns {
+            svg += &format!(
+                "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"orange\"/>\n",
+                x * SVG_TILE + SVG_TILE / 2,
+                y * SVG_TILE + SVG_TILE / 2,
+                SVG_TILE / 3
+            );
+        }
+        for (point, fill) in [(self.start, "blue"), (self.end, "red")] {
+            svg += &rect(point, fill);
+        }
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const SMALL: &str = "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
+
+    #[test]
+    fn renders_best_paths() {
+        let maze = parse(SMALL);
+        let paths = maze.best_paths().unwrap();
+        assert_eq!(paths.score, 7036);
+        assert_eq!(paths.tiles.len(), 45);
+        let picture = maze.render(&paths, false);
+        assert_eq!(
+            picture,
+            "\
+###############
+#.......#....E#
+#.#.###.#.###O#
+#.....#.#...#O#
+#.###.#####.#O#
+#.#.#.......#O#
+#.#.#####.###O#
+#..+O+OOOOO+#O#
+###O#O#####O#O#
+#+O+#O....#O#O#
+#O#O#O###.#O#O#
+#+O+O+#...#O#O#
+#O###.#.#.#O#O#
+#S..#.....#+O+#
+###############
+"
+        );
+    }
+
+    #[test]
+    fn exports_svg() {
+        let maze = parse(SMALL);
+        let paths = maze.best_paths().unwrap();
+        let svg = maze.svg(&paths);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"150\""));
+        assert_eq!(svg.matches("fill=\"#4c4\"").count(), paths.tiles.len());
+        assert_eq!(svg.matches("<circle").count(), paths.turns.len());
+    }
+}
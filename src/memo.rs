@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// Cache for recursive functions, keyed on the function arguments.
+///
+/// The recursive function receives the memo as its first argument and calls back into
+/// [`Memo::get_or_insert_with`] for each sub-problem, passing the closure that computes it from
+/// the memo it is handed back.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+/// Snapshot of the cache usage of a [`Memo`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the cached value for `key`, or computes it with `f` and caches it.
+    ///
+    /// `f` receives the memo back so it can recurse into other keys.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = f(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Returns the cached value for `key` without computing it. Does not count as a hit or miss.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Drops every cached value and resets the statistics.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.reset_stats();
+    }
+
+    /// Resets hit and miss counters, keeping the cached values.
+    pub fn reset_stats(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoStats {
+    /// Number of lookups, i.e. hits and misses.
+    pub fn lookups(&self) -> usize {
+        self.hits + self.misses
+    }
+
+    /// Share of lookups answered from the cache, in `[0, 1]`. Returns `0` before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        match self.lookups() {
+            0 => 0.0,
+            n => self.hits as f64 / n as f64,
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits / {} misses ({:.1}% hit rate), {} entries",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.entries
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Memo, MemoStats};
+
+    fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.get_or_insert_with(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))
+    }
+
+    #[test]
+    fn computes_recursive_values() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(90, &mut memo), 2880067194370816120);
+        assert_eq!(memo.get(&10), Some(&55));
+    }
+
+    #[test]
+    fn tracks_hits_and_misses() {
+        let mut memo = Memo::new();
+        fib(10, &mut memo);
+        // Keys 2..=10 are each computed once, keys 2..=8 are then looked up again.
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 7,
+                misses: 9,
+                entries: 9
+            }
+        );
+        fib(10, &mut memo);
+        assert_eq!(memo.stats().hits, 8);
+    }
+
+    #[test]
+    fn clear_resets_cache_and_stats() {
+        let mut memo = Memo::new();
+        fib(10, &mut memo);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), MemoStats::default());
+        assert_eq!(memo.stats().hit_rate(), 0.0);
+    }
+}
@@ -0,0 +1,287 @@
+//! The corrupted multiplication memory from day 3.
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+/// Longest text a token can start with before its operands, `don't()`.
+const LONGEST_KEYWORD: usize = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Mul(a, b) => write!(f, "mul({a},{b})"),
+            Token::Do => write!(f, "do()"),
+            Token::Dont => write!(f, "don't()"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Matching `mul(`, `do()` or `don't()`, with the number of bytes matched so far.
+    Keyword(usize),
+    /// Reading the operand `index` of a `mul`, with its value and number of digits.
+    Operand {
+        index: usize,
+        first: u32,
+        value: u32,
+        digits: u32,
+    },
+}
+
+/// Single-pass scanner fed one byte at a time, so input can be streamed.
+///
+/// No token contains the first byte of a token after its own start, so when a byte breaks the
+/// current match it is tried again as the start of a new token and nothing is missed.
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    state: State,
+    buffer: [u8; LONGEST_KEYWORD],
+    /// Offset of the next byte.
+    offset: usize,
+    /// Offset of the token being matched.
+    start: usize,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Self {
+            state: State::Keyword(0),
+            buffer: [0; LONGEST_KEYWORD],
+            offset: 0,
+            start: 0,
+        }
+    }
+
+    /// Feeds the next byte, returning a token with its byte offset when one is complete.
+    pub fn push(&mut self, byte: u8) -> Option<(usize, Token)> {
+        let offset = self.offset;
+        self.offset += 1;
+        match self.advance(byte) {
+            Step::Continue => None,
+            Step::Token(token) => {
+                self.state = State::Keyword(0);
+                Some((self.start, token))
+            }
+            Step::Mismatch => {
+                let restart = self.state != State::Keyword(0);
+                self.state = State::Keyword(0);
+                if restart {
+                    self.offset = offset;
+                    self.push(byte)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn advance(&mut self, byte: u8) -> Step {
+        match self.state {
+            State::Keyword(matched) => {
+                if matched == 0 {
+                    self.start = self.offset - 1;
+                }
+                self.buffer[matched] = byte;
+                let text = &self.buffer[..=matched];
+                match text {
+                    b"mul(" => {
+                        self.state = State::Operand {
+                            index: 0,
+                            first: 0,
+                            value: 0,
+                            digits: 0,
+                        };
+                        Step::Continue
+                    }
+                    b"do()" => Step::Token(Token::Do),
+                    b"don't()" => Step::Token(Token::Dont),
+                    _ if [&b"mul("[..], b"do()", b"don't()"]
+                        .iter()
+                        .any(|keyword| keyword.starts_with(text)) =>
+                    {
+                        self.state = State::Keyword(matched + 1);
+                        Step::Continue
+                    }
+                    _ => Step::Mismatch,
+                }
+            }
+            State::Operand {
+                index,
+                first,
+                value,
+                digits,
+            } => match byte {
+                b'0'..=b'9' if digits < 3 => {
+                    self.state = State::Operand {
+                        index,
+                        first,
+                        value: value * 10 + u32::from(byte - b'0'),
+                        digits: digits + 1,
+                    };
+                    Step::Continue
+                }
+                b',' if index == 0 && digits > 0 => {
+                    self.state = State::Operand {
+                        index: 1,
+                        first: value,
+                        value: 0,
+                        digits: 0,
+                    };
+                    Step::Continue
+                }
+                b')' if index == 1 && digits > 0 => Step::Token(Token::Mul(first, value)),
+                _ => Step::Mismatch,
+            },
+        }
+    }
+}
+
+enum Step {
+    Continue,
+    Token(Token),
+    Mismatch,
+}
+
+/// All tokens of `input` with their byte offsets.
+pub fn tokenize(input: &str) -> Vec<(usize, Token)> {
+    let mut scanner = Scanner::new();
+    input.bytes().filter_map(|b| scanner.push(b)).collect()
+}
+
+/// What the interpreter did with a token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Counted(u64),
+    Skipped,
+    Enabled,
+    Disabled,
+}
+
+/// Adds up the multiplications, honouring `do()` and `don't()` when `conditionals` is set.
+#[derive(Clone, Debug)]
+pub struct Interpreter {
+    conditionals: bool,
+    enabled: bool,
+    sum: u64,
+}
+
+impl Interpreter {
+    pub fn new(conditionals: bool) -> Self {
+        Self {
+            conditionals,
+            enabled: true,
+            sum: 0,
+        }
+    }
+
+    pub fn sum(&self) -> u64 {
+        self.sum
+    }
+
+    pub fn execute(&mut self, token: Token) -> Outcome {
+        match token {
+            Token::Mul(a, b) if self.enabled || !self.conditionals => {
+                let product = u64::from(a) * u64::from(b);
+                self.sum += product;
+                Outcome::Counted(product)
+            }
+            Token::Mul(..) => Outcome::Skipped,
+            Token::Do => {
+                self.enabled = true;
+                Outcome::Enabled
+            }
+            Token::Dont => {
+                self.enabled = false;
+                Outcome::Disabled
+            }
+        }
+    }
+
+    /// Runs every token of `input`, writing one line per token to `debug` if given.
+    pub fn run(&mut self, input: impl Read, mut debug: Option<&mut dyn Write>) -> io::Result<u64> {
+        let mut scanner = Scanner::new();
+        for byte in io::BufReader::new(input).bytes() {
+            if let Some((offset, token)) = scanner.push(byte?) {
+                let outcome = self.execute(token);
+                if let Some(out) = debug.as_mut() {
+                    let what = match outcome {
+                        Outcome::Counted(product) => format!("counted {product}"),
+                        Outcome::Skipped => "skipped".to_string(),
+                        Outcome::Enabled => "enabled".to_string(),
+                        Outcome::Disabled => "disabled".to_string(),
+                    };
+                    writeln!(out, "{offset:>6}: {:<12} {what}", token.to_string())?;
+                }
+            }
+        }
+        Ok(self.sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Interpreter, Token};
+
+    const EXAMPLE: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn tokenizes_with_offsets() {
+        assert_eq!(
+            tokenize(EXAMPLE),
+            [
+                (1, Token::Mul(2, 4)),
+                (20, Token::Dont),
+                (28, Token::Mul(5, 5)),
+                (48, Token::Mul(11, 8)),
+                (59, Token::Do),
+                (64, Token::Mul(8, 5)),
+            ]
+        );
+        assert_eq!(
+            tokenize("mumul(1,2)mul(1234,5)mul(123,4)ddo()mul(1,)"),
+            [
+                (2, Token::Mul(1, 2)),
+                (21, Token::Mul(123, 4)),
+                (32, Token::Do)
+            ]
+        );
+    }
+
+    #[test]
+    fn interprets_stream() {
+        let mut log = vec![];
+        let mut interpreter = Interpreter::new(true);
+        let sum = interpreter.run(EXAMPLE.as_bytes(), Some(&mut log)).unwrap();
+        assert_eq!(sum, 48);
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "     1: mul(2,4)     counted 8
+    20: don't()      disabled
+    28: mul(5,5)     skipped
+    48: mul(11,8)    skipped
+    59: do()         enabled
+    64: mul(8,5)     counted 40
+"
+        );
+        assert_eq!(
+            Interpreter::new(false)
+                .run(EXAMPLE.as_bytes(), None)
+                .unwrap(),
+            161
+        );
+    }
+}
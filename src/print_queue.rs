@@ -0,0 +1,330 @@
+//! The safety manual updates and page ordering rules from day 5.
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use crate::toposort::{CycleError, Dag};
+
+#[derive(Clone, Debug)]
+pub struct PrintQueue {
+    /// Edge `a -> b` for each rule `a|b`.
+    pub rules: Dag<u32>,
+    pub updates: Vec<Vec<u32>>,
+}
+
+pub fn parse(input: &str) -> PrintQueue {
+    let (rules, updates) = input
+        .split_once("\n\n")
+        .expect("rules, blank line, updates");
+    PrintQueue {
+        rules: rules
+            .lines()
+            .map(|line| {
+                let (before, after) = line.split_once('|').expect("rule a|b");
+                (
+                    before.trim().parse().unwrap(),
+                    after.trim().parse().unwrap(),
+                )
+            })
+            .collect(),
+        updates: updates
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split(',').map(|page| page.parse().unwrap()).collect())
+            .collect(),
+    }
+}
+
+/// A correct order for an update, moving as few pages as possible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reordering {
+    pub order: Vec<u32>,
+    /// Pages not kept in place, in printed order. The others keep their relative order.
+    pub moved: Vec<u32>,
+}
+
+/// What is wrong with an update and how to fix it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub update: Vec<u32>,
+    /// Pairs of pages `(a, b)` with `a` printed before `b` although a rule puts `b` first.
+    pub violations: Vec<(u32, u32)>,
+    /// A minimal reordering, or a cycle among the rules between the pages of the update.
+    pub fixed: Result<Reordering, CycleError<u32>>,
+}
+
+impl PrintQueue {
+    pub fn diagnose(&self, update: &[u32]) -> Diagnosis {
+        let induced = self.rules.induced(update.iter().copied());
+        let violations = update
+            .iter()
+            .tuple_combinations()
+            .filter(|&(a, b)| induced.has_edge(b, a))
+            .map(|(&a, &b)| (a, b))
+            .collect();
+        Diagnosis {
+            update: update.to_vec(),
+            violations,
+            fixed: reorder(update, &induced),
+        }
+    }
+}
+
+/// Finds a correct order of `update` keeping as many pages as possible where they are.
+///
+/// Pages can stay in place together unless a later one must come, through any chain of rules,
+/// before an earlier one. Those conflicts form a partial order on the pages, so the largest set
+/// of pages without conflicts is a maximum antichain, found from a maximum matching by König's
+/// theorem. The kept pages are then chained in printed order and the rest is sorted around them.
+fn reorder(update: &[u32], rules: &Dag<u32>) -> Result<Reordering, CycleError<u32>> {
+    rules.sort()?;
+    let n = update.len();
+    // reach[i][j]: page i must come before page j.
+    let mut reach = vec![vec![false; n]; n];
+    for (i, row) in reach.iter_mut().enumerate() {
+        let mut stack = vec![i];
+        while let Some(from) = stack.pop() {
+            for to in 0..n {
+                if !row[to] && rules.has_edge(&update[from], &update[to]) {
+                    row[to] = true;
+                    stack.push(to);
+                }
+            }
+        }
+    }
+    let conflict: Vec<Vec<bool>> = (0..n)
+        .map(|i| (0..n).map(|j| i < j && reach[j][i]).collect())
+        .collect();
+    let kept = max_antichain(&conflict);
+
+    let mut constrained = rules.clone();
+    let kept_pages: Vec<u32> = (0..n).filter(|&i| kept[i]).map(|i| update[i]).collect();
+    for pair in kept_pages.windows(2) {
+        constrained.add_edge(pair[0], pair[1]);
+    }
+    Ok(Reordering {
+        order: constrained.sort_stable()?,
+        moved: (0..n).filter(|&i| !kept[i]).map(|i| update[i]).collect(),
+    })
+}
+
+/// Largest set of elements pairwise unrelated by the strict partial order `less`.
+fn max_antichain(less: &[Vec<bool>]) -> Vec<bool> {
+    let n = less.len();
+    fn augment(
+        u: usize,
+        less: &[Vec<bool>],
+        seen: &mut [bool],
+        match_right: &mut [Option<usize>],
+    ) -> bool {
+        for v in 0..less.len() {
+            if less[u][v] && !seen[v] {
+                seen[v] = true;
+                if match_right[v].is_none_or(|w| augment(w, less, seen, match_right)) {
+                    match_right[v] = Some(u);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+    let mut match_right = vec![None; n];
+    let matched_left: Vec<bool> = (0..n)
+        .map(|u| augment(u, less, &mut vec![false; n], &mut match_right))
+        .collect();
+
+    // Alternating paths from the unmatched left copies give the minimum vertex cover, and the
+    // elements with neither copy in it form the antichain.
+    let mut left: Vec<bool> = matched_left.iter().map(|&m| !m).collect();
+    let mut right = vec![false; n];
+    let mut stack: Vec<usize> = (0..n).filter(|&u| left[u]).collect();
+    while let Some(u) = stack.pop() {
+        for v in 0..n {
+            if less[u][v] && !right[v] {
+                right[v] = true;
+                if let Some(w) = match_right[v].filter(|&w| !left[w]) {
+                    left[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+    }
+    (0..n).map(|x| left[x] && !right[x]).collect()
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.update.iter().join(","))?;
+        if self.violations.is_empty() {
+            return writeln!(f, "ok");
+        }
+        let broken = self
+            .violations
+            .iter()
+            .map(|(a, b)| format!("{b} must come before {a}"))
+            .join(", ");
+        writeln!(f, "{broken}")?;
+        match &self.fixed {
+            Ok(fixed) => writeln!(
+                f,
+                "  fixed: {} (moving {})",
+                fixed.order.iter().join(","),
+                fixed.moved.iter().join(",")
+            ),
+            Err(cycle) => writeln!(f, "  cannot be fixed, rules contain a {cycle}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{parse, PrintQueue};
+    use crate::toposort::Dag;
+
+    const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    #[test]
+    fn diagnoses_example() {
+        let queue = parse(EXAMPLE);
+        let report: String = queue
+            .updates
+            .iter()
+            .map(|update| queue.diagnose(update).to_string())
+            .collect();
+        assert_eq!(
+            report,
+            "\
+75,47,61,53,29: ok
+97,61,53,29,13: ok
+75,29,13: ok
+75,97,47,61,53: 97 must come before 75
+  fixed: 97,75,47,61,53 (moving 75)
+61,13,29: 29 must come before 13
+  fixed: 61,29,13 (moving 13)
+97,13,75,29,47: 75 must come before 13, 29 must come before 13, 47 must come before 13, 47 must come before 29
+  fixed: 97,75,47,29,13 (moving 13,29)
+"
+        );
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let queue = PrintQueue {
+            rules: [(1, 2), (2, 3), (3, 1), (4, 1)].into_iter().collect(),
+            updates: vec![],
+        };
+        let diagnosis = queue.diagnose(&[4, 3, 2, 1000]);
+        assert_eq!(diagnosis.violations, [(3, 2)]);
+        assert_eq!(
+            diagnosis.to_string(),
+            "4,3,2,1000: 2 must come before 3\n  fixed: 4,2,3,1000 (moving 3)\n"
+        );
+        let diagnosis = queue.diagnose(&[1, 2, 3]);
+        assert_eq!(diagnosis.violations, [(1, 3)]);
+        assert_eq!(
+            diagnosis.to_string(),
+            "1,2,3: 3 must come before 1\n  cannot be fixed, rules contain a cycle: 1 -> 2 -> 3 -> 1\n"
+        );
+    }
+
+    /// Length of the longest common subsequence.
+    fn lcs(a: &[u32], b: &[u32]) -> usize {
+        let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in 0..a.len() {
+            for j in 0..b.len() {
+                table[i + 1][j + 1] = if a[i] == b[j] {
+                    table[i][j] + 1
+                } else {
+                    table[i][j + 1].max(table[i + 1][j])
+                };
+            }
+        }
+        table[a.len()][b.len()]
+    }
+
+    #[test]
+    fn moves_fewest_pages() {
+        let queue = PrintQueue {
+            rules: [(1, 3), (2, 5), (3, 5)].into_iter().collect(),
+            updates: vec![],
+        };
+        let fixed = queue.diagnose(&[2, 3, 4, 5, 1]).fixed.unwrap();
+        assert_eq!(fixed.order, [2, 1, 3, 4, 5]);
+        assert_eq!(fixed.moved, [1]);
+
+        // Against every correct order of small random updates.
+        let mut state = 5u64;
+        let mut random = |n: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % n
+        };
+        for _ in 0..300 {
+            let pages: Vec<u32> = (1..=6).collect();
+            let mut rules = Dag::new();
+            for (a, b) in pages.iter().tuple_combinations() {
+                // Rules only go from lower to higher pages, so there is no cycle.
+                if random(3) == 0 {
+                    rules.add_edge(*a, *b);
+                }
+            }
+            let queue = PrintQueue {
+                rules,
+                updates: vec![],
+            };
+            let mut update = pages.clone();
+            for i in (1..update.len()).rev() {
+                update.swap(i, random(i as u64 + 1) as usize);
+            }
+            let induced = queue.rules.induced(update.iter().copied());
+            let best = update
+                .iter()
+                .copied()
+                .permutations(update.len())
+                .filter(|order| induced.respects(order))
+                .map(|order| lcs(&update, &order))
+                .max()
+                .unwrap();
+            let fixed = queue.diagnose(&update).fixed.unwrap();
+            assert!(induced.respects(&fixed.order), "{update:?}");
+            assert_eq!(fixed.moved.len(), update.len() - best, "{update:?}");
+            assert_eq!(
+                lcs(&update, &fixed.order),
+                best,
+                "kept pages stay in place: {update:?}"
+            );
+        }
+    }
+}
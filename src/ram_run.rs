@@ -0,0 +1,190 @@
+//! The memory space filling up with falling bytes from day 18.
+use std::collections::VecDeque;
+
+use crate::grid::{Grid, Point};
+use crate::template::{ANSI_BOLD, ANSI_GREEN, ANSI_RED, ANSI_RESET};
+use crate::union_find::UnionFind;
+
+/// Square memory space crossed from the top left to the bottom right corner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemorySpace {
+    pub size: usize,
+    /// Falling bytes, in order.
+    pub bytes: Vec<Point>,
+    /// Number of bytes fallen when part one is asked.
+    pub fallen: usize,
+}
+
+/// Reads the byte positions. The example uses a smaller memory space than the puzzle input.
+pub fn parse(input: &str) -> MemorySpace {
+    let bytes: Vec<Point> = input
+        .lines()
+        .map(|line| {
+            let (x, y) = line.split_once(',').expect("x,y");
+            (x.parse().unwrap(), y.parse().unwrap())
+        })
+        .collect();
+    let (size, fallen) = if bytes.len() > 1024 {
+        (71, 1024)
+    } else {
+        (7, 12)
+    };
+    MemorySpace {
+        size,
+        bytes,
+        fallen,
+    }
+}
+
+impl MemorySpace {
+    pub fn start(&self) -> Point {
+        (0, 0)
+    }
+
+    pub fn exit(&self) -> Point {
+        (self.size - 1, self.size - 1)
+    }
+
+    /// Corrupted cells once the first `n` bytes have fallen.
+    pub fn corrupted_after(&self, n: usize) -> Grid<bool> {
+        let mut grid = Grid::new(self.size, self.size, false);
+        for &byte in self.bytes.iter().take(n) {
+            grid[byte] = true;
+        }
+        grid
+    }
+
+    /// Shortest path from the start to the exit avoiding `corrupted` cells, both included.
+    pub fn shortest_path(&self, corrupted: &Grid<bool>) -> Option<Vec<Point>> {
+        let (start, exit) = (self.start(), self.exit());
+        if corrupted[start] {
+            return None;
+        }
+        let mut parent: Grid<Option<Point>> = corrupted.map(|_| None);
+        parent[start] = Some(start);
+        let mut queue = VecDeque::from([start]);
+        while let Some(point) = queue.pop_front() {
+            if point == exit {
+                let mut path = vec![exit];
+                while let Some(&last) = path.last().filter(|&&last| last != start) {
+                    path.push(parent[last].expect("visited cells have a parent"));
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in corrupted.neighbours(point) {
+                if !corrupted[next] && parent[next].is_none() {
+                    parent[next] = Some(point);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Index of the first byte after which the exit cannot be reached.
+    ///
+    /// Lets every byte fall, then takes them back from the last one, joining each freed cell
+    /// with its free neighbours. The byte whose removal connects the start to the exit is the
+    /// one that cut them apart.
+    pub fn first_blocking_byte(&self) -> Option<usize> {
+        let mut first_fall = Grid::new(self.size, self.size, None);
+        for (i, &byte) in self.bytes.iter().enumerate() {
+            first_fall[byte].get_or_insert(i);
+        }
+        let corrupted_before = |point: Point, i: usize| first_fall[point].is_some_and(|f| f < i);
+
+        let mut sets = UnionFind::new(self.size * self.size);
+        let (start, exit) = (
+            first_fall.index_of(self.start()),
+            first_fall.index_of(self.exit()),
+        );
+        let join = |sets: &mut UnionFind, point: Point, i: usize| {
+            for next in first_fall.neighbours(point) {
+                if !corrupted_before(next, i) {
+                    sets.union(first_fall.index_of(point), first_fall.index_of(next));
+                }
+            }
+        };
+        let all = self.bytes.len();
+        for point in first_fall.positions() {
+            if !corrupted_before(point, all) {
+                join(&mut sets, point, all);
+            }
+        }
+        if sets.connected(start, exit) {
+            return None;
+        }
+        for i in (0..all).rev() {
+            let byte = self.bytes[i];
+            if first_fall[byte] == Some(i) {
+                join(&mut sets, byte, i);
+                if sets.connected(start, exit) {
+                    return Some(i);
+                }
+            }
+        }
+        None
+    }
+
+    /// The memory space just before the blocking byte falls, with the last open path as `O` and
+    /// the blocking byte as `X`, in colour when `ansi` is set.
+    pub fn render_blocked(&self, ansi: bool) -> Option<String> {
+        let blocking = self.first_blocking_byte()?;
+        let corrupted = self.corrupted_after(blocking);
+        let path = self.shortest_path(&corrupted)?;
+        let mut cells = corrupted.map(|&c| if c { '#' } else { '.' });
+        for &point in &path {
+            cells[point] = 'O';
+        }
+        cells[self.bytes[blocking]] = 'X';
+
+        let mut out = String::new();
+        for row in cells.rows() {
+            for &c in row {
+                match (ansi, c) {
+                    (true, 'O') => out += &format!("{ANSI_GREEN}O{ANSI_RESET}"),
+                    (true, 'X') => out += &format!("{ANSI_BOLD}{ANSI_RED}X{ANSI_RESET}"),
+                    _ => out.push(c),
+                }
+            }
+            out.push('\n');
+        }
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const EXAMPLE: &str = "5,4\n4,2\n4,5\n3,0\n2,1\n6,3\n2,4\n1,5\n0,6\n3,3\n2,6\n5,1\n1,2\n5,5\n2,5\n6,5\n1,4\n0,4\n6,4\n1,1\n6,1\n1,0\n0,5\n1,6\n2,0\n";
+
+    #[test]
+    fn finds_blocking_byte() {
+        let space = parse(EXAMPLE);
+        assert_eq!(space.first_blocking_byte(), Some(20));
+        assert_eq!(space.bytes[20], (6, 1));
+        for n in 0..space.bytes.len() {
+            let open = space.shortest_path(&space.corrupted_after(n)).is_some();
+            assert_eq!(open, n <= 20, "after {n} bytes");
+        }
+    }
+
+    #[test]
+    fn renders_last_open_path() {
+        let space = parse(EXAMPLE);
+        assert_eq!(
+            space.render_blocked(false).unwrap(),
+            "\
+O..#OOO
+O##OO#X
+O#OO#OO
+OOO#OO#
+###OO##
+.##O###
+#.#OOOO
+"
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::grid::Point;
+
+/// Closed polygon given by its corner points, without repeating the first one.
+///
+/// Corner `(x, y)` is the top-left corner of cell `(x, y)`, so a cell spans `[x, x + 1] x [y, y + 1]`.
+pub type Polygon = Vec<Point>;
+
+/// Inclusive range of cells covered by a [`Region`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+/// A set of grid cells, analysed as a shape made of unit squares.
+///
+/// Cells belong to the same shape when they share an edge. Cells that only touch by a corner
+/// delimit separate sides, as fences around garden plots do.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Region {
+    cells: HashSet<Point>,
+}
+
+impl Region {
+    pub fn new(cells: impl IntoIterator<Item = Point>) -> Self {
+        Self {
+            cells: cells.into_iter().collect(),
+        }
+    }
+
+    pub fn cells(&self) -> &HashSet<Point> {
+        &self.cells
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains(&point)
+    }
+
+    /// Whether the cell at `point + offset` belongs to the region.
+    fn contains_offset(&self, (x, y): Point, (dx, dy): (isize, isize)) -> bool {
+        match (x.checked_add_signed(dx), y.checked_add_signed(dy)) {
+            (Some(x), Some(y)) => self.cells.contains(&(x, y)),
+            _ => false,
+        }
+    }
+
+    /// Number of cells.
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Number of cell edges between the region and the outside, holes included.
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|&cell| {
+                [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .into_iter()
+                    .filter(|&offset| !self.contains_offset(cell, offset))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight sides, holes included.
+    ///
+    /// A polygon has as many sides as corners, so this counts for each cell the convex
+    /// (both orthogonal neighbours outside) and concave (both inside, diagonal outside) corners.
+    pub fn sides(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|&cell| {
+                [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+                    .into_iter()
+                    .filter(|&(dx, dy)| {
+                        let horizontal = self.contains_offset(cell, (dx, 0));
+                        let vertical = self.contains_offset(cell, (0, dy));
+                        let diagonal = self.contains_offset(cell, (dx, dy));
+                        (!horizontal && !vertical) || (horizontal && vertical && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Smallest box containing every cell, `None` for an empty region.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let min_x = self.cells.iter().map(|&(x, _)| x).min()?;
+        let max_x = self.cells.iter().map(|&(x, _)| x).max()?;
+        let min_y = self.cells.iter().map(|&(_, y)| y).min()?;
+        let max_y = self.cells.iter().map(|&(_, y)| y).max()?;
+        Some(BoundingBox {
+            min: (min_x, min_y),
+            max: (max_x, max_y),
+        })
+    }
+
+    /// Groups of cells outside the region that cannot reach infinity, sorted by their first cell.
+    ///
+    /// Outside cells connect through corners too, since the region does not.
+    pub fn holes(&self) -> Vec<Region> {
+        let Some(BoundingBox { min, max }) = self.bounding_box() else {
+            return vec![];
+        };
+        // Work in a frame with a one cell margin, so that the outside is connected.
+        let (width, height) = (max.0 - min.0 + 3, max.1 - min.1 + 3);
+        let is_free =
+            |(x, y): Point| x == 0 || y == 0 || !self.contains((x + min.0 - 1, y + min.1 - 1));
+
+        let mut seen = vec![false; width * height];
+        let mut components: Vec<Vec<Point>> = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                if seen[y * width + x] || !is_free((x, y)) {
+                    continue;
+                }
+                seen[y * width + x] = true;
+                let mut component = vec![];
+                let mut stack = vec![(x, y)];
+                while let Some((cx, cy)) = stack.pop() {
+                    component.push((cx, cy));
+                    for dy in -1..=1 {
+                        for dx in -1..=1 {
+                            let (Some(nx), Some(ny)) =
+                                (cx.checked_add_signed(dx), cy.checked_add_signed(dy))
+                            else {
+                                continue;
+                            };
+                            if nx < width
+                                && ny < height
+                                && !seen[ny * width + nx]
+                                && is_free((nx, ny))
+                            {
+                                seen[ny * width + nx] = true;
+                                stack.push((nx, ny));
+                            }
+                        }
+                    }
+                }
+                components.push(component);
+            }
+        }
+
+        // The first component starts in the margin corner, it is the outside.
+        components
+            .into_iter()
+            .skip(1)
+            .map(|component| {
+                Region::new(
+                    component
+                        .into_iter()
+                        .map(|(x, y)| (x + min.0 - 1, y + min.1 - 1)),
+                )
+            })
+            .collect()
+    }
+
+    /// Every boundary loop of the region: outer outlines run clockwise, hole outlines
+    /// counter-clockwise (with `y` pointing down). Only corner points are kept.
+    ///
+    /// Loops are sorted by their top-left corner, so outer outlines come before their holes.
+    pub fn boundaries(&self) -> Vec<Polygon> {
+        // Directed unit edges with the region on their right hand side.
+        let mut edges: HashMap<Point, Vec<Point>> = HashMap::new();
+        for &(x, y) in &self.cells {
+            let mut add = |from: Point, to: Point| edges.entry(from).or_default().push(to);
+            if !self.contains_offset((x, y), (0, -1)) {
+                add((x, y), (x + 1, y));
+            }
+            if !self.contains_offset((x, y), (1, 0)) {
+                add((x + 1, y), (x + 1, y + 1));
+            }
+            if !self.contains_offset((x, y), (0, 1)) {
+                add((x + 1, y + 1), (x, y + 1));
+            }
+            if !self.contains_offset((x, y), (-1, 0)) {
+                add((x, y + 1), (x, y));
+            }
+        }
+
+        let mut starts: Vec<Point> = edges.keys().copied().collect();
+        starts.sort_unstable_by_key(|&(x, y)| (y, x));
+
+        // Starting from the top-left-most remaining vertex guarantees that it is a corner.
+        let mut polygons = vec![];
+        for start in starts {
+            while let Some(first) = edges.get_mut(&start).and_then(Vec::pop) {
+                let mut polygon = vec![start];
+                let (mut from, mut to) = (start, first);
+                loop {
+                    let outgoing = edges.get_mut(&to).expect("boundary edges form loops");
+                    // At a pinch point prefer turning right, which keeps cells touching by a
+                    // corner on separate loops.
+                    let best = outgoing
+                        .iter()
+                        .copied()
+                        .enumerate()
+                        .max_by_key(|&(_, next)| turn_priority(from, to, next));
+                    if to == start
+                        && best.is_none_or(|(_, next)| {
+                            turn_priority(from, to, first) > turn_priority(from, to, next)
+                        })
+                    {
+                        break;
+                    }
+                    let (index, next) = best.expect("boundary edges form loops");
+                    outgoing.swap_remove(index);
+                    if direction(from, to) != direction(to, next) {
+                        polygon.push(to);
+                    }
+                    (from, to) = (to, next);
+                }
+                polygons.push(polygon);
+            }
+        }
+        polygons
+    }
+
+    /// Outer boundary of the region, starting from the top-left corner and running clockwise.
+    pub fn outline(&self) -> Option<Polygon> {
+        self.boundaries().into_iter().next()
+    }
+}
+
+impl FromIterator<Point> for Region {
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+fn direction(from: Point, to: Point) -> (isize, isize) {
+    (
+        (to.0 as isize - from.0 as isize).signum(),
+        (to.1 as isize - from.1 as isize).signum(),
+    )
+}
+
+/// Right turns rank highest, then straight lines, then left turns.
+fn turn_priority(from: Point, via: Point, to: Point) -> isize {
+    let (ax, ay) = direction(from, via);
+    let (bx, by) = direction(via, to);
+    // With `y` pointing down a positive cross product is a clockwise (right) turn.
+    let cross = ax * by - ay * bx;
+    let dot = ax * bx + ay * by;
+    2 * cross + dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundingBox, Region};
+    use crate::grid::Grid;
+
+    fn region(input: &str) -> Region {
+        Grid::parse(input)
+            .iter()
+            .filter(|(_, &c)| c == '#')
+            .map(|(point, _)| point)
+            .collect()
+    }
+
+    #[test]
+    fn measures_square() {
+        let square = region("##\n##\n");
+        assert_eq!(square.area(), 4);
+        assert_eq!(square.perimeter(), 8);
+        assert_eq!(square.sides(), 4);
+        assert_eq!(square.outline(), Some(vec![(0, 0), (2, 0), (2, 2), (0, 2)]));
+    }
+
+    #[test]
+    fn measures_region_with_holes() {
+        let ring = region("#####\n#.#.#\n#####\n");
+        assert_eq!(ring.area(), 13);
+        assert_eq!(ring.perimeter(), 24);
+        assert_eq!(ring.sides(), 12);
+        assert_eq!(
+            ring.bounding_box(),
+            Some(BoundingBox {
+                min: (0, 0),
+                max: (4, 2)
+            })
+        );
+
+        let holes = ring.holes();
+        assert_eq!(holes, vec![Region::new([(1, 1)]), Region::new([(3, 1)])]);
+
+        let boundaries = ring.boundaries();
+        assert_eq!(boundaries.len(), 3);
+        assert_eq!(boundaries[0], vec![(0, 0), (5, 0), (5, 3), (0, 3)]);
+        assert_eq!(boundaries[1], vec![(1, 1), (1, 2), (2, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn counts_sides_at_pinch_points() {
+        // Example from day 12: the inner regions touch the outer one only by a corner.
+        let shape = region("######\n###..#\n###..#\n#..###\n#..###\n######\n");
+        assert_eq!(shape.sides(), 12);
+        assert_eq!(shape.holes().len(), 1);
+        let corners: usize = shape.boundaries().iter().map(Vec::len).sum();
+        assert_eq!(corners, shape.sides());
+    }
+
+    #[test]
+    fn counts_concave_sides() {
+        let e_shape = region("#####\n#....\n#####\n#....\n#####\n");
+        assert_eq!(e_shape.sides(), 12);
+        assert_eq!(e_shape.perimeter(), 36);
+        assert!(e_shape.holes().is_empty());
+        assert_eq!(e_shape.outline().map(|outline| outline.len()), Some(12));
+    }
+}
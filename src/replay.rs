@@ -0,0 +1,251 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+const ANSI_CLEAR: &str = "\x1b[2J\x1b[H";
+
+const HELP: &str =
+    "<enter> step  b back  p play/pause  + faster  - slower  g <n> go to frame  q quit";
+
+/// One recorded state of a simulation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub caption: String,
+    pub content: String,
+}
+
+/// Sequence of frames that can be played back in the terminal or written to a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Replay {
+    frames: Vec<Frame>,
+}
+
+/// What the player should do after a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+/// Playback state, driven by text commands.
+#[derive(Clone, Debug)]
+pub struct Player<'a> {
+    replay: &'a Replay,
+    frame: usize,
+    playing: bool,
+    delay: Duration,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, caption: impl Into<String>, content: impl ToString) {
+        self.frames.push(Frame {
+            caption: caption.into(),
+            content: content.to_string(),
+        });
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Renders the frames at `indices`, skipping the ones out of range.
+    pub fn render(&self, indices: impl IntoIterator<Item = usize>) -> String {
+        indices
+            .into_iter()
+            .filter_map(|i| Some((i, self.frames.get(i)?)))
+            .map(|(i, frame)| format!("Frame {i}: {}\n{}\n", frame.caption, frame.content))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Writes the frames at `indices` to `path`.
+    pub fn dump(
+        &self,
+        path: impl AsRef<Path>,
+        indices: impl IntoIterator<Item = usize>,
+    ) -> io::Result<()> {
+        fs::write(path, self.render(indices))
+    }
+
+    /// Plays the replay in the terminal, reading commands from stdin.
+    pub fn play(&self, delay: Duration) -> io::Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        // Reading stdin blocks, so it runs on its own thread. The thread ends with the process.
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                if line.is_err() || sender.send(line.unwrap_or_default()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut player = Player::new(self, delay);
+        let mut out = io::stdout();
+        loop {
+            player.draw(&mut out)?;
+            let command = if player.is_playing() {
+                match receiver.recv_timeout(player.delay) {
+                    Ok(line) => Some(line),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => Some("q".into()),
+                }
+            } else {
+                Some(receiver.recv().unwrap_or_else(|_| "q".into()))
+            };
+            let control = match command {
+                Some(line) => player.command(&line),
+                None => {
+                    player.tick();
+                    Control::Continue
+                }
+            };
+            if control == Control::Quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<'a> Player<'a> {
+    pub fn new(replay: &'a Replay, delay: Duration) -> Self {
+        Self {
+            replay,
+            frame: 0,
+            playing: false,
+            delay,
+        }
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    fn last(&self) -> usize {
+        self.replay.len().saturating_sub(1)
+    }
+
+    /// Advances one frame while playing, pausing on the last one.
+    pub fn tick(&mut self) {
+        if self.playing {
+            self.frame = (self.frame + 1).min(self.last());
+            self.playing = self.frame < self.last();
+        }
+    }
+
+    pub fn command(&mut self, line: &str) -> Control {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            None | Some("s") => {
+                self.playing = false;
+                self.frame = (self.frame + 1).min(self.last());
+            }
+            Some("b") => {
+                self.playing = false;
+                self.frame = self.frame.saturating_sub(1);
+            }
+            Some("p") => self.playing = !self.playing && self.frame < self.last(),
+            Some("+") => self.delay = (self.delay / 2).max(Duration::from_millis(1)),
+            Some("-") => self.delay *= 2,
+            Some("g") => {
+                if let Some(Ok(frame)) = words.next().map(str::parse::<usize>) {
+                    self.playing = false;
+                    self.frame = frame.min(self.last());
+                }
+            }
+            Some("q") => return Control::Quit,
+            Some(_) => {}
+        }
+        Control::Continue
+    }
+
+    pub fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "{ANSI_CLEAR}")?;
+        if let Some(frame) = self.replay.frames.get(self.frame) {
+            writeln!(
+                out,
+                "{ANSI_BOLD}Frame {}/{}{ANSI_RESET} {} ({}, {}ms per frame)",
+                self.frame,
+                self.last(),
+                frame.caption,
+                if self.playing { "playing" } else { "paused" },
+                self.delay.as_millis()
+            )?;
+            writeln!(out, "{}", frame.content)?;
+        }
+        writeln!(out, "{HELP}")?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Control, Player, Replay};
+
+    fn replay() -> Replay {
+        let mut replay = Replay::new();
+        for i in 0..4 {
+            replay.record(format!("step {i}"), i);
+        }
+        replay
+    }
+
+    #[test]
+    fn steps_and_plays() {
+        let replay = replay();
+        let mut player = Player::new(&replay, Duration::from_millis(100));
+        player.command("");
+        player.command("s");
+        assert_eq!(player.frame(), 2);
+        player.command("b");
+        assert_eq!(player.frame(), 1);
+
+        player.command("p");
+        assert!(player.is_playing());
+        player.tick();
+        player.tick();
+        player.tick();
+        assert_eq!(player.frame(), 3);
+        assert!(!player.is_playing());
+
+        player.command("g 0");
+        player.command("+");
+        assert_eq!(player.frame(), 0);
+        assert_eq!(player.delay(), Duration::from_millis(50));
+        assert_eq!(player.command("q"), Control::Quit);
+    }
+
+    #[test]
+    fn renders_selected_frames() {
+        assert_eq!(
+            replay().render([1, 3, 9]),
+            "Frame 1: step 1\n1\n\nFrame 3: step 3\n3\n"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! The reactor safety reports from day 2.
+use std::fmt::Display;
+
+/// Largest difference allowed between adjacent levels.
+const MAX_STEP: i32 = 3;
+
+/// Why a step between two adjacent levels is unsafe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// The levels go the other way than the first step.
+    DirectionChange,
+    /// The levels differ by more than 3.
+    TooLarge,
+    /// The levels are equal.
+    Flat,
+}
+
+/// First unsafe step of a report, ending at level `index`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub index: usize,
+    pub reason: Reason,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    /// Safe once the level at this index is removed, the first index that works.
+    Dampened(usize),
+    Unsafe,
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::DirectionChange => write!(f, "direction change"),
+            Reason::TooLarge => write!(f, "step too large"),
+            Reason::Flat => write!(f, "flat step"),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Vec<Vec<i32>> {
+    input
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|num| num.parse().unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+/// Whether going from `a` to `b` is a safe step in `direction`, 1 or -1.
+fn safe_step(a: i32, b: i32, direction: i32) -> bool {
+    (1..=MAX_STEP).contains(&((b - a) * direction))
+}
+
+pub fn first_violation(levels: &[i32]) -> Option<Violation> {
+    let direction = (levels.get(1)? - levels[0]).signum();
+    levels.windows(2).enumerate().find_map(|(i, pair)| {
+        let step = pair[1] - pair[0];
+        let reason = if step == 0 {
+            Reason::Flat
+        } else if step.signum() != direction {
+            Reason::DirectionChange
+        } else if step.abs() > MAX_STEP {
+            Reason::TooLarge
+        } else {
+            return None;
+        };
+        Some(Violation {
+            index: i + 1,
+            reason,
+        })
+    })
+}
+
+/// Checks a report, allowing the removal of one level.
+///
+/// For each direction, one pass from each end records how far the levels stay safe. Removing
+/// level `k` works when the levels before it and after it are safe on their own and the two
+/// levels around it make a safe step, so every candidate is checked in constant time.
+pub fn check(levels: &[i32]) -> Verdict {
+    if first_violation(levels).is_none() {
+        return Verdict::Safe;
+    }
+    let n = levels.len();
+    [1, -1]
+        .into_iter()
+        .filter_map(|direction| {
+            let mut prefix = vec![true; n];
+            for i in 1..n {
+                prefix[i] = prefix[i - 1] && safe_step(levels[i - 1], levels[i], direction);
+            }
+            let mut suffix = vec![true; n];
+            for i in (0..n - 1).rev() {
+                suffix[i] = suffix[i + 1] && safe_step(levels[i], levels[i + 1], direction);
+            }
+            (0..n).find(|&k| {
+                (k == 0 || prefix[k - 1])
+                    && (k == n - 1 || suffix[k + 1])
+                    && (k == 0 || k == n - 1 || safe_step(levels[k - 1], levels[k + 1], direction))
+            })
+        })
+        .min()
+        .map_or(Verdict::Unsafe, Verdict::Dampened)
+}
+
+/// One line telling whether the report is safe, and if not where and why it fails and which
+/// level the dampener removes.
+pub fn explain(levels: &[i32]) -> String {
+    let report = levels
+        .iter()
+        .map(i32::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let Some(violation) = first_violation(levels) else {
+        return format!("{report}: safe");
+    };
+    let fix = match check(levels) {
+        Verdict::Dampened(k) => format!("safe without level {k} ({})", levels[k]),
+        _ => "no single removal helps".to_string(),
+    };
+    format!(
+        "{report}: {} at level {} ({}), {fix}",
+        violation.reason, violation.index, levels[violation.index]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, explain, parse, Verdict};
+
+    const EXAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+    #[test]
+    fn explains_example() {
+        let lines: Vec<String> = parse(EXAMPLE).iter().map(|r| explain(r)).collect();
+        assert_eq!(
+            lines,
+            [
+                "7 6 4 2 1: safe",
+                "1 2 7 8 9: step too large at level 2 (7), no single removal helps",
+                "9 7 6 2 1: step too large at level 3 (2), no single removal helps",
+                "1 3 2 4 5: direction change at level 2 (2), safe without level 1 (3)",
+                "8 6 4 4 1: flat step at level 3 (4), safe without level 2 (4)",
+                "1 3 6 7 9: safe",
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_removing_each_level() {
+        let reports = [
+            vec![5, 1, 2, 3],
+            vec![1, 2, 3, 9],
+            vec![3, 1, 2, 3, 4],
+            vec![1, 5, 6, 7],
+            vec![4, 4, 4],
+            vec![1, 2, 1, 2],
+            vec![10, 7, 8, 6, 4],
+        ];
+        for levels in reports {
+            let expected = (0..levels.len()).find(|&k| {
+                let mut removed = levels.clone();
+                removed.remove(k);
+                super::first_violation(&removed).is_none()
+            });
+            let verdict = check(&levels);
+            assert_eq!(
+                verdict,
+                expected.map_or(Verdict::Unsafe, Verdict::Dampened),
+                "{levels:?}"
+            );
+        }
+    }
+}
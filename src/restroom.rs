@@ -0,0 +1,272 @@
+//! The robots walking around the bathroom from day 14.
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use num::Integer;
+use regex::Regex;
+
+use crate::grid::Grid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Robot {
+    pub position: (i64, i64),
+    pub velocity: (i64, i64),
+}
+
+/// Robots on a wrapping area. Each coordinate comes back to its start every `width` or `height`
+/// seconds, so the whole picture repeats with a period of their least common multiple.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bathroom {
+    pub robots: Vec<Robot>,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// How [`Bathroom::find_tree`] recognises the frame with the picture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Score {
+    /// Robots are bunched together, so their coordinates have the lowest variance. Each axis is
+    /// scored on its own period and the two best offsets are combined.
+    Variance,
+    /// The picture is the largest group of robots touching each other. Every frame of the period
+    /// is scored.
+    LargestCluster,
+}
+
+pub fn parse(input: &str) -> Vec<Robot> {
+    let re = Regex::new(r"p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)").unwrap();
+    input
+        .lines()
+        .map(|line| {
+            let caps = re.captures(line).expect("robot description");
+            Robot {
+                position: (caps[1].parse().unwrap(), caps[2].parse().unwrap()),
+                velocity: (caps[3].parse().unwrap(), caps[4].parse().unwrap()),
+            }
+        })
+        .collect()
+}
+
+impl Bathroom {
+    pub fn new(robots: Vec<Robot>, width: usize, height: usize) -> Self {
+        Self {
+            robots,
+            width,
+            height,
+        }
+    }
+
+    /// Number of seconds after which every robot is back at its start.
+    pub fn period(&self) -> usize {
+        self.width.lcm(&self.height)
+    }
+
+    fn xs_at(&self, second: usize) -> impl Iterator<Item = i64> + '_ {
+        let (second, width) = (second as i64, self.width as i64);
+        self.robots
+            .iter()
+            .map(move |r| (r.position.0 + r.velocity.0 * second).rem_euclid(width))
+    }
+
+    fn ys_at(&self, second: usize) -> impl Iterator<Item = i64> + '_ {
+        let (second, height) = (second as i64, self.height as i64);
+        self.robots
+            .iter()
+            .map(move |r| (r.position.1 + r.velocity.1 * second).rem_euclid(height))
+    }
+
+    /// Robot positions after `second` seconds.
+    pub fn positions_at(&self, second: usize) -> Vec<(usize, usize)> {
+        self.xs_at(second)
+            .zip(self.ys_at(second))
+            .map(|(x, y)| (x as usize, y as usize))
+            .collect()
+    }
+
+    /// Number of robots on each tile after `second` seconds.
+    pub fn counts_at(&self, second: usize) -> Grid<usize> {
+        let mut grid = Grid::new(self.width, self.height, 0);
+        for position in self.positions_at(second) {
+            grid[position] += 1;
+        }
+        grid
+    }
+
+    /// Robots in each quadrant after `second` seconds, ignoring the middle row and column.
+    pub fn quadrants_at(&self, second: usize) -> [usize; 4] {
+        let (mid_x, mid_y) = (self.width / 2, self.height / 2);
+        let mut count = [0; 4];
+        for (x, y) in self.positions_at(second) {
+            if x != mid_x && y != mid_y {
+                count[usize::from(x > mid_x) + 2 * usize::from(y > mid_y)] += 1;
+            }
+        }
+        count
+    }
+
+    /// Size of the largest group of occupied tiles connected horizontally or vertically.
+    pub fn largest_cluster_at(&self, second: usize) -> usize {
+        let occupied = self.counts_at(second).map(|&count| count > 0);
+        let components = occupied.components(|a, b| a == b);
+        components
+            .cells
+            .iter()
+            .filter(|cells| occupied[cells[0]])
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// First second within the period where the robots draw the picture, according to `score`.
+    ///
+    /// With [`Score::Variance`], the best x offset modulo the width and the best y offset modulo
+    /// the height are combined with the Chinese remainder theorem. Returns `None` if the width and
+    /// height are not coprime and the offsets are incompatible.
+    pub fn find_tree(&self, score: Score) -> Option<usize> {
+        match score {
+            Score::Variance => {
+                let x = (0..self.width).min_by_key(|&t| spread(self.xs_at(t)))?;
+                let y = (0..self.height).min_by_key(|&t| spread(self.ys_at(t)))?;
+                crt((x, self.width), (y, self.height))
+            }
+            Score::LargestCluster => (0..self.period())
+                .rev()
+                .max_by_key(|&t| self.largest_cluster_at(t)),
+        }
+    }
+
+    /// Plain PBM image of the tiles holding at least one robot after `second` seconds.
+    pub fn pbm(&self, second: usize) -> String {
+        let counts = self.counts_at(second);
+        let mut image = format!("P1\n{} {}\n", self.width, self.height);
+        for row in counts.rows() {
+            let pixels: Vec<&str> = row
+                .iter()
+                .map(|&count| if count > 0 { "1" } else { "0" })
+                .collect();
+            image += &pixels.join(" ");
+            image.push('\n');
+        }
+        image
+    }
+
+    /// Plain PGM image where brighter tiles hold more robots after `second` seconds.
+    pub fn pgm(&self, second: usize) -> String {
+        let counts = self.counts_at(second);
+        let max = counts.iter().map(|(_, &count)| count).max().unwrap_or(0);
+        let mut image = format!("P2\n{} {}\n{}\n", self.width, self.height, max.max(1));
+        for row in counts.rows() {
+            let pixels: Vec<String> = row.iter().map(usize::to_string).collect();
+            image += &pixels.join(" ");
+            image.push('\n');
+        }
+        image
+    }
+
+    /// Writes the frame after `second` seconds as a PGM image if `path` ends in `.pgm`, or as a
+    /// PBM image otherwise.
+    pub fn write_frame(&self, path: impl AsRef<Path>, second: usize) -> io::Result<()> {
+        let path = path.as_ref();
+        let image = match path.extension().and_then(|e| e.to_str()) {
+            Some("pgm") => self.pgm(second),
+            _ => self.pbm(second),
+        };
+        fs::write(path, image)
+    }
+}
+
+/// Variance of `values` multiplied by the square of their count, which keeps it an integer.
+fn spread(values: impl Iterator<Item = i64>) -> i64 {
+    let (n, sum, squares) = values.fold((0, 0, 0), |(n, sum, squares), v| {
+        (n + 1, sum + v, squares + v * v)
+    });
+    n * squares - sum * sum
+}
+
+/// Smallest `t` with `t = a mod m` and `t = b mod n`.
+fn crt((a, m): (usize, usize), (b, n): (usize, usize)) -> Option<usize> {
+    let (a, m, b, n) = (a as i64, m as i64, b as i64, n as i64);
+    let gcd = m.extended_gcd(&n);
+    if (b - a) % gcd.gcd != 0 {
+        return None;
+    }
+    let lcm = m / gcd.gcd * n;
+    let k = ((b - a) / gcd.gcd * gcd.x).rem_euclid(n / gcd.gcd);
+    Some((a + m * k).rem_euclid(lcm) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, Bathroom, Robot, Score};
+
+    const TREE_AT: i64 = 97;
+
+    /// A 4x5 block of robots drawn at `TREE_AT`, among robots scattered over the area.
+    fn bathroom() -> Bathroom {
+        let (width, height) = (11, 13);
+        let mut robots = vec![];
+        let mut seed = 7;
+        let mut next = |modulo: i64| {
+            seed = (seed * 1103515245 + 12345) % (1 << 31);
+            seed % modulo
+        };
+        for i in 0..20 {
+            let velocity = (next(width) - 5, next(height) - 6);
+            let target = (3 + i % 4, 4 + i / 4);
+            robots.push(Robot {
+                position: (
+                    (target.0 - velocity.0 * TREE_AT).rem_euclid(width),
+                    (target.1 - velocity.1 * TREE_AT).rem_euclid(height),
+                ),
+                velocity,
+            });
+        }
+        for _ in 0..6 {
+            robots.push(Robot {
+                position: (next(width), next(height)),
+                velocity: (next(width) - 5, next(height) - 6),
+            });
+        }
+        Bathroom::new(robots, width as usize, height as usize)
+    }
+
+    #[test]
+    fn combines_offsets() {
+        assert_eq!(crt((3, 11), (5, 13)), Some(135));
+        assert_eq!(crt((1, 4), (3, 6)), Some(9));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+    }
+
+    #[test]
+    fn finds_tree() {
+        let bathroom = bathroom();
+        assert_eq!(bathroom.period(), 143);
+        assert_eq!(bathroom.find_tree(Score::Variance), Some(TREE_AT as usize));
+        assert_eq!(
+            bathroom.find_tree(Score::LargestCluster),
+            Some(TREE_AT as usize)
+        );
+        assert!(bathroom.largest_cluster_at(TREE_AT as usize) >= 20);
+    }
+
+    #[test]
+    fn draws_images() {
+        let bathroom = Bathroom::new(
+            vec![
+                Robot {
+                    position: (0, 0),
+                    velocity: (1, 0),
+                },
+                Robot {
+                    position: (2, 1),
+                    velocity: (1, 0),
+                },
+            ],
+            3,
+            2,
+        );
+        assert_eq!(bathroom.pbm(1), "P1\n3 2\n0 1 0\n1 0 0\n");
+        assert_eq!(bathroom.pgm(1), "P2\n3 2\n1\n0 1 0\n1 0 0\n");
+    }
+}
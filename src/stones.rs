@@ -0,0 +1,259 @@
+//! The splitting stones from day 11.
+//!
+//! Stones never interact and their order does not matter for counting, so the line of stones is
+//! kept as a map from engraved value to number of stones.
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::memo::{Memo, MemoStats};
+
+/// Stone multiplicity. The counts grow exponentially, so adding must either detect overflow or
+/// wrap around a modulus.
+pub trait Count: Copy {
+    const ZERO: Self;
+    const ONE: Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+impl Count for u64 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    fn checked_add(self, other: Self) -> Option<Self> {
+        u64::checked_add(self, other)
+    }
+}
+
+impl Count for u128 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    fn checked_add(self, other: Self) -> Option<Self> {
+        u128::checked_add(self, other)
+    }
+}
+
+/// Count modulo `M`, which never overflows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Modular<const M: u64>(pub u64);
+
+impl<const M: u64> Count for Modular<M> {
+    const ZERO: Self = Modular(0);
+    const ONE: Self = Modular(1 % M);
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Modular(
+            ((self.0 as u128 + other.0 as u128) % M as u128) as u64,
+        ))
+    }
+}
+
+/// A new stone value does not fit in a `u64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValueOverflow;
+
+/// Transformation of a stone. [`Rule::apply`] returns `None` when the rule does not apply to the
+/// stone, and otherwise the new stones or the overflow of one of their values.
+#[derive(Clone, Copy, Debug)]
+pub struct Rule {
+    pub name: &'static str,
+    pub apply: fn(u64) -> Option<Result<Vec<u64>, ValueOverflow>>,
+}
+
+pub const ZERO_TO_ONE: Rule = Rule {
+    name: "0 becomes 1",
+    apply: |stone| (stone == 0).then(|| Ok(vec![1])),
+};
+
+pub const SPLIT_EVEN_DIGITS: Rule = Rule {
+    name: "even number of digits splits in two",
+    apply: |stone| {
+        let digits = stone.checked_ilog10()? + 1;
+        let half = 10u64.pow(digits / 2);
+        (digits % 2 == 0).then(|| Ok(vec![stone / half, stone % half]))
+    },
+};
+
+pub const MULTIPLY_2024: Rule = Rule {
+    name: "multiplied by 2024",
+    apply: |stone| {
+        Some(
+            stone
+                .checked_mul(2024)
+                .map(|v| vec![v])
+                .ok_or(ValueOverflow),
+        )
+    },
+};
+
+/// The rules from the puzzle, the first one that applies is used.
+pub const PUZZLE_RULES: &[Rule] = &[ZERO_TO_ONE, SPLIT_EVEN_DIGITS, MULTIPLY_2024];
+
+/// Why [`Stones::blink`] could not go on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlinkError {
+    /// The number of stones does not fit in the count type, or a stone value in a `u64`.
+    Overflow { blink: usize },
+    /// None of the rules applies to this stone.
+    NoRule { blink: usize, stone: u64 },
+}
+
+impl Display for BlinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overflow { blink } => write!(f, "stones overflow at blink {blink}"),
+            Self::NoRule { blink, stone } => {
+                write!(f, "no rule applies to stone {stone} at blink {blink}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlinkError {}
+
+/// Stones counted by value.
+#[derive(Clone, Debug)]
+pub struct Stones<'a, C> {
+    counts: HashMap<u64, C>,
+    rules: &'a [Rule],
+    blinks: usize,
+    /// Distinct values before the first blink and after each blink.
+    distinct: Vec<usize>,
+    /// Transformations already computed, as the same values come back every blink.
+    cache: Memo<u64, Option<Result<Vec<u64>, ValueOverflow>>>,
+}
+
+impl<'a, C: Count> Stones<'a, C> {
+    pub fn new(stones: &[u64], rules: &'a [Rule]) -> Result<Self, BlinkError> {
+        let mut counts = HashMap::new();
+        for &stone in stones {
+            add(&mut counts, stone, C::ONE).ok_or(BlinkError::Overflow { blink: 0 })?;
+        }
+        Ok(Self {
+            distinct: vec![counts.len()],
+            counts,
+            rules,
+            blinks: 0,
+            cache: Memo::new(),
+        })
+    }
+
+    pub fn blinks(&self) -> usize {
+        self.blinks
+    }
+
+    /// Number of stones for each value.
+    pub fn counts(&self) -> &HashMap<u64, C> {
+        &self.counts
+    }
+
+    /// Number of distinct values before the first blink, then after each blink.
+    pub fn distinct_history(&self) -> &[usize] {
+        &self.distinct
+    }
+
+    /// Usage of the cache of stone transformations.
+    pub fn cache_stats(&self) -> MemoStats {
+        self.cache.stats()
+    }
+
+    pub fn blink(&mut self) -> Result<(), BlinkError> {
+        let blink = self.blinks + 1;
+        let mut next = HashMap::with_capacity(self.counts.len());
+        for (&stone, &count) in &self.counts {
+            let rules = self.rules;
+            let children = match self
+                .cache
+                .get_or_insert_with(stone, |_| rules.iter().find_map(|rule| (rule.apply)(stone)))
+            {
+                Some(Ok(children)) => children,
+                Some(Err(ValueOverflow)) => return Err(BlinkError::Overflow { blink }),
+                None => return Err(BlinkError::NoRule { blink, stone }),
+            };
+            for child in children {
+                add(&mut next, child, count).ok_or(BlinkError::Overflow { blink })?;
+            }
+        }
+        self.counts = next;
+        self.blinks = blink;
+        self.distinct.push(self.counts.len());
+        Ok(())
+    }
+
+    pub fn blink_times(&mut self, n: usize) -> Result<(), BlinkError> {
+        (0..n).try_for_each(|_| self.blink())
+    }
+
+    /// Total number of stones.
+    pub fn total(&self) -> Result<C, BlinkError> {
+        self.counts
+            .values()
+            .try_fold(C::ZERO, |total, &count| total.checked_add(count))
+            .ok_or(BlinkError::Overflow { blink: self.blinks })
+    }
+}
+
+fn add<C: Count>(counts: &mut HashMap<u64, C>, stone: u64, count: C) -> Option<()> {
+    let entry = counts.entry(stone).or_insert(C::ZERO);
+    *entry = entry.checked_add(count)?;
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlinkError, Modular, Rule, Stones, PUZZLE_RULES, ZERO_TO_ONE};
+
+    #[test]
+    fn counts_example() {
+        let mut stones = Stones::<u64>::new(&[125, 17], PUZZLE_RULES).unwrap();
+        stones.blink_times(6).unwrap();
+        assert_eq!(stones.total(), Ok(22));
+        assert_eq!(stones.distinct_history()[..5], [2, 3, 4, 5, 8]);
+        // Each distinct value is looked up once per blink and transformed only once.
+        let stats = stones.cache_stats();
+        assert_eq!(stats.lookups(), stones.distinct_history()[..6].iter().sum());
+        assert_eq!(stats.misses, stats.entries);
+        assert_eq!(stats.hits, 8);
+        stones.blink_times(19).unwrap();
+        assert_eq!(stones.total(), Ok(55312));
+    }
+
+    #[test]
+    fn counts_thousands_of_blinks() {
+        let mut exact = Stones::<u128>::new(&[125, 17], PUZZLE_RULES).unwrap();
+        assert!(matches!(
+            exact.blink_times(1000),
+            Err(BlinkError::Overflow { .. })
+        ));
+
+        let mut modular = Stones::<Modular<1_000>>::new(&[125, 17], PUZZLE_RULES).unwrap();
+        modular.blink_times(75).unwrap();
+        assert_eq!(modular.total(), Ok(Modular(482)));
+        modular.blink_times(2000).unwrap();
+        assert_eq!(modular.blinks(), 2075);
+        // The set of values reached is finite, so the number of distinct values settles.
+        let history = modular.distinct_history();
+        assert_eq!(history[1000], history[2075]);
+    }
+
+    #[test]
+    fn uses_custom_rules() {
+        const DOUBLE: Rule = Rule {
+            name: "doubles",
+            apply: |stone| Some(Ok(vec![stone, stone])),
+        };
+        let mut stones = Stones::<u64>::new(&[0, 5], &[ZERO_TO_ONE, DOUBLE]).unwrap();
+        stones.blink_times(3).unwrap();
+        assert_eq!(stones.total(), Ok(4 + 8));
+
+        let mut stuck = Stones::<u64>::new(&[3], &[ZERO_TO_ONE]).unwrap();
+        assert_eq!(
+            stuck.blink(),
+            Err(BlinkError::NoRule { blink: 1, stone: 3 })
+        );
+    }
+
+    #[test]
+    fn reports_value_overflow() {
+        // 17 digits, odd, so the stone is multiplied and no longer fits.
+        let mut stones = Stones::<u64>::new(&[10_000_000_000_000_001], PUZZLE_RULES).unwrap();
+        assert_eq!(stones.blink(), Err(BlinkError::Overflow { blink: 1 }));
+    }
+}
@@ -0,0 +1,14 @@
+use crate::antennas::{distinct, parse, Harmonics};
+use crate::template::commands::read_input;
+
+pub fn handle(path: &str, harmonics: Harmonics) {
+    let input = read_input(path);
+
+    let antennas = parse(&input);
+    let antinodes = antennas.antinodes(harmonics, |p| antennas.in_bounds(p));
+    println!("{}", antennas.render(&antinodes, true));
+    for (frequency, positions) in &antinodes {
+        println!("{frequency}: {} antinodes", positions.len());
+    }
+    println!("Distinct antinodes: {}", distinct(&antinodes).len());
+}
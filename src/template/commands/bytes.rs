@@ -0,0 +1,16 @@
+use crate::ram_run::parse;
+use crate::template::commands::read_input;
+
+pub fn handle(path: &str) {
+    let input = read_input(path);
+
+    let space = parse(&input);
+    match (space.first_blocking_byte(), space.render_blocked(true)) {
+        (Some(i), Some(picture)) => {
+            let (x, y) = space.bytes[i];
+            print!("{picture}");
+            println!("Byte {i} at {x},{y} blocks the exit");
+        }
+        _ => println!("The exit stays reachable after all bytes have fallen"),
+    }
+}
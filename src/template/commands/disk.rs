@@ -0,0 +1,23 @@
+use crate::disk::{checksum, parse};
+use crate::template::commands::read_input;
+
+/// Largest disk drawn block by block.
+const MAX_RENDERED: u64 = 200;
+
+pub fn handle(path: &str, whole_files: bool) {
+    let input = read_input(path);
+
+    let disk = parse(&input);
+    let segments = if whole_files {
+        disk.compact_files()
+    } else {
+        disk.compact_blocks()
+    };
+    if disk.size() <= MAX_RENDERED {
+        println!("{}", disk.render(&disk.layout()));
+        println!("{}", disk.render(&segments));
+    } else {
+        println!("Disk of {} blocks, too large to draw", disk.size());
+    }
+    println!("Checksum: {}", checksum(&segments));
+}
@@ -0,0 +1,10 @@
+use crate::garden::parse;
+use crate::template::commands::read_input;
+
+pub fn handle(path: &str) {
+    let input = read_input(path);
+
+    let garden = parse(&input);
+    println!("{}", garden.render());
+    print!("{}", garden.report());
+}
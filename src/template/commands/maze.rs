@@ -0,0 +1,30 @@
+use std::{fs, process};
+
+use crate::maze::parse;
+use crate::template::commands::read_input;
+
+pub fn handle(path: &str, svg: Option<&str>) {
+    let input = read_input(path);
+
+    let maze = parse(&input);
+    let Some(paths) = maze.best_paths() else {
+        eprintln!("no path from start to end in \"{path}\"");
+        process::exit(1);
+    };
+
+    match svg {
+        Some(out) => {
+            if let Err(e) = fs::write(out, maze.svg(&paths)) {
+                eprintln!("failed to write \"{out}\": {e}");
+                process::exit(1);
+            }
+        }
+        None => print!("{}", maze.render(&paths, true)),
+    }
+    println!(
+        "Score {}, {} tiles on best paths, {} turn points",
+        paths.score,
+        paths.tiles.len(),
+        paths.turns.len()
+    );
+}
@@ -1,6 +1,25 @@
 pub mod all;
+pub mod antennas;
+pub mod bytes;
+pub mod disk;
 pub mod download;
+pub mod garden;
+pub mod maze;
+pub mod mull;
 pub mod read;
+pub mod replay;
+pub mod reports;
 pub mod scaffold;
 pub mod solve;
+pub mod step;
 pub mod time;
+
+use std::{fs, process};
+
+/// Reads the input file at `path`, exiting with an error if it cannot be read.
+pub fn read_input(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("failed to read input \"{path}\": {e}");
+        process::exit(1);
+    })
+}
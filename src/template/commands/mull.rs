@@ -0,0 +1,24 @@
+use std::io::{self, Write};
+use std::{fs, process};
+
+use crate::mull::Interpreter;
+
+pub fn handle(path: &str, all: bool, debug: bool) {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("failed to open input \"{path}\": {e}");
+            process::exit(1);
+        }
+    };
+
+    let mut stdout = io::stdout().lock();
+    let log = debug.then_some(&mut stdout as &mut dyn Write);
+    match Interpreter::new(!all).run(file, log) {
+        Ok(sum) => println!("Sum of multiplications: {sum}"),
+        Err(e) => {
+            eprintln!("failed to read input \"{path}\": {e}");
+            process::exit(1);
+        }
+    }
+}
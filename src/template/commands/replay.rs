@@ -0,0 +1,35 @@
+use std::process;
+use std::time::Duration;
+
+use crate::template::commands::read_input;
+use crate::warehouse::record;
+
+pub fn handle(path: &str, wide: bool, delay: u64, dump: Option<&str>, frames: Option<&str>) {
+    let input = read_input(path);
+
+    if frames.is_some() && dump.is_none() {
+        eprintln!("--frames selects the frames to write with --dump");
+        process::exit(1);
+    }
+
+    let replay = record(&input, wide);
+    let indices: Vec<usize> = match frames {
+        Some(frames) => match frames.split(',').map(|i| i.trim().parse()).collect() {
+            Ok(indices) => indices,
+            Err(e) => {
+                eprintln!("failed to parse frames \"{frames}\": {e}");
+                process::exit(1);
+            }
+        },
+        None => (0..replay.len()).collect(),
+    };
+
+    let result = match dump {
+        Some(out) => replay.dump(out, indices),
+        None => replay.play(Duration::from_millis(delay)),
+    };
+    if let Err(e) = result {
+        eprintln!("failed to replay \"{path}\": {e}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,19 @@
+use crate::reports::{check, explain, parse, Verdict};
+use crate::template::commands::read_input;
+
+pub fn handle(path: &str, explain_each: bool) {
+    let input = read_input(path);
+
+    let reports = parse(&input);
+    if explain_each {
+        for report in &reports {
+            println!("{}", explain(report));
+        }
+        println!();
+    }
+    let verdicts: Vec<Verdict> = reports.iter().map(|report| check(report)).collect();
+    let safe = verdicts.iter().filter(|&&v| v == Verdict::Safe).count();
+    let dampened = verdicts.iter().filter(|&&v| v != Verdict::Unsafe).count();
+    println!("Safe reports: {safe}");
+    println!("Safe with the dampener: {dampened}");
+}
@@ -0,0 +1,23 @@
+use std::{io, process};
+
+use crate::chronospatial::{debugger, Machine, Program};
+use crate::template::commands::read_input;
+
+/// Large enough for puzzle programs, small enough to notice an infinite loop.
+const STEP_LIMIT: usize = 10_000_000;
+
+pub fn handle(path: &str) {
+    let program: Program = match read_input(path).parse() {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("failed to parse program \"{path}\": {e}");
+            process::exit(1);
+        }
+    };
+
+    let mut machine = Machine::new(&program).with_step_limit(STEP_LIMIT);
+    if let Err(e) = debugger::debug(&mut machine, io::stdin().lock(), io::stdout()) {
+        eprintln!("failed to run debugger: {e}");
+        process::exit(1);
+    }
+}
@@ -14,6 +14,15 @@ mod timings;
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
+pub const ANSI_RED: &str = "\x1b[31m";
+pub const ANSI_GREEN: &str = "\x1b[32m";
+pub const ANSI_YELLOW: &str = "\x1b[33m";
+pub const ANSI_BLACK: &str = "\x1b[30m";
+
+/// Escape code setting the background to `colour` of the 256 colour palette.
+pub fn ansi_background(colour: u8) -> String {
+    format!("\x1b[48;5;{colour}m")
+}
 
 /// Helper function that reads a text file to a string.
 #[must_use]
@@ -0,0 +1,286 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// Directed graph over hashable nodes, where an edge `a -> b` means `a` must come before `b`.
+///
+/// Nodes keep their insertion order, which [`Dag::sort_stable`] uses to break ties.
+#[derive(Clone, Debug)]
+pub struct Dag<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+/// Returned when the graph cannot be ordered. Holds one cycle, each node having an edge to the
+/// next one and the last node an edge to the first one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError<N> {
+    pub cycle: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash> Dag<N> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            index: HashMap::new(),
+            successors: vec![],
+            predecessors: vec![],
+        }
+    }
+
+    /// Adds `node` if not already present and returns its insertion index.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        let i = self.nodes.len();
+        self.index.insert(node.clone(), i);
+        self.nodes.push(node);
+        self.successors.push(vec![]);
+        self.predecessors.push(vec![]);
+        i
+    }
+
+    /// Adds the constraint that `before` comes before `after`, adding missing nodes.
+    pub fn add_edge(&mut self, before: N, after: N) {
+        let (from, to) = (self.add_node(before), self.add_node(after));
+        if !self.successors[from].contains(&to) {
+            self.successors[from].push(to);
+            self.predecessors[to].push(from);
+        }
+    }
+
+    /// Nodes in insertion order.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    pub fn has_edge(&self, before: &N, after: &N) -> bool {
+        match (self.index.get(before), self.index.get(after)) {
+            (Some(&from), Some(&to)) => self.successors[from].contains(&to),
+            _ => false,
+        }
+    }
+
+    /// All edges, grouped by source node in insertion order.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        self.successors
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, to)| {
+                to.iter()
+                    .map(move |&to| (&self.nodes[from], &self.nodes[to]))
+            })
+    }
+
+    /// Subgraph made of `subset` and the edges between its nodes. Nodes are inserted in the
+    /// order of `subset`; those unknown to `self` are added without edges.
+    pub fn induced(&self, subset: impl IntoIterator<Item = N>) -> Self {
+        let mut induced = Self::new();
+        let mut kept = vec![];
+        for node in subset {
+            if let Some(&i) = self.index.get(&node) {
+                kept.push(i);
+            }
+            induced.add_node(node);
+        }
+        for &from in &kept {
+            for &to in &self.successors[from] {
+                if induced.contains(&self.nodes[to]) {
+                    induced.add_edge(self.nodes[from].clone(), self.nodes[to].clone());
+                }
+            }
+        }
+        induced
+    }
+
+    /// Whether `order` places every node before its successors. Nodes missing from `order` are
+    /// not constrained.
+    pub fn respects(&self, order: &[N]) -> bool {
+        let position: HashMap<&N, usize> = order.iter().enumerate().map(|(i, n)| (n, i)).collect();
+        self.edges().all(
+            |(before, after)| match (position.get(before), position.get(after)) {
+                (Some(b), Some(a)) => b < a,
+                _ => true,
+            },
+        )
+    }
+
+    /// Orders the nodes with Kahn's algorithm, processing ready nodes first in first out.
+    pub fn sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        self.kahn(VecDeque::new())
+    }
+
+    /// Orders the nodes with Kahn's algorithm, always picking the ready node inserted first.
+    /// Ties are broken by node insertion order, so the same nodes and edges give the same result
+    /// as long as the nodes are added in the same order.
+    pub fn sort_stable(&self) -> Result<Vec<N>, CycleError<N>> {
+        self.kahn(BinaryHeap::new())
+    }
+
+    /// Returns a cycle if the graph has one.
+    pub fn find_cycle(&self) -> Option<Vec<N>> {
+        self.sort().err().map(|error| error.cycle)
+    }
+
+    fn kahn(&self, mut ready: impl ReadyQueue) -> Result<Vec<N>, CycleError<N>> {
+        let mut in_degree: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        for (i, &degree) in in_degree.iter().enumerate() {
+            if degree == 0 {
+                ready.push(i);
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(i) = ready.pop() {
+            order.push(self.nodes[i].clone());
+            for &next in &self.successors[i] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            Ok(order)
+        } else {
+            Err(CycleError {
+                cycle: self.cycle_among(&in_degree),
+            })
+        }
+    }
+
+    /// Finds a cycle among the nodes Kahn's algorithm could not process. Each of them still
+    /// has an unprocessed predecessor, so walking predecessors must loop. The cycle starts at
+    /// its earliest inserted node.
+    fn cycle_among(&self, in_degree: &[usize]) -> Vec<N> {
+        let mut current = in_degree
+            .iter()
+            .position(|&d| d > 0)
+            .expect("unprocessed nodes remain");
+        let mut visited_at = HashMap::new();
+        let mut walk = vec![];
+        while !visited_at.contains_key(&current) {
+            visited_at.insert(current, walk.len());
+            walk.push(current);
+            current = *self.predecessors[current]
+                .iter()
+                .find(|&&p| in_degree[p] > 0)
+                .expect("unprocessed nodes have unprocessed predecessors");
+        }
+        let mut cycle: Vec<usize> = walk[visited_at[&current]..].iter().rev().copied().collect();
+        let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+        cycle.rotate_left(first);
+        cycle.into_iter().map(|i| self.nodes[i].clone()).collect()
+    }
+}
+
+/// Set of nodes ready to be ordered, deciding which one Kahn's algorithm picks next.
+trait ReadyQueue {
+    fn push(&mut self, i: usize);
+    fn pop(&mut self) -> Option<usize>;
+}
+
+impl ReadyQueue for VecDeque<usize> {
+    fn push(&mut self, i: usize) {
+        self.push_back(i);
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.pop_front()
+    }
+}
+
+impl ReadyQueue for BinaryHeap<Reverse<usize>> {
+    fn push(&mut self, i: usize) {
+        BinaryHeap::push(self, Reverse(i));
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        BinaryHeap::pop(self).map(|Reverse(i)| i)
+    }
+}
+
+impl<N: Clone + Eq + Hash> Default for Dag<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Clone + Eq + Hash> FromIterator<(N, N)> for Dag<N> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(iter: I) -> Self {
+        let mut dag = Self::new();
+        for (before, after) in iter {
+            dag.add_edge(before, after);
+        }
+        dag
+    }
+}
+
+impl<N: Debug> Display for CycleError<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle: ")?;
+        for node in &self.cycle {
+            write!(f, "{node:?} -> ")?;
+        }
+        match self.cycle.first() {
+            Some(first) => write!(f, "{first:?}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: Debug> std::error::Error for CycleError<N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CycleError, Dag};
+
+    #[test]
+    fn sorts_nodes() {
+        let dag: Dag<&str> = [("shirt", "tie"), ("tie", "jacket"), ("trousers", "shoes")]
+            .into_iter()
+            .collect();
+        let order = dag.sort().unwrap();
+        assert!(dag.respects(&order));
+        assert_eq!(
+            dag.sort_stable().unwrap(),
+            ["shirt", "tie", "jacket", "trousers", "shoes"]
+        );
+    }
+
+    #[test]
+    fn restricts_to_subset() {
+        let dag: Dag<u32> = [(1, 2), (2, 3), (1, 3), (3, 4)].into_iter().collect();
+        let induced = dag.induced([4, 1, 3]);
+        assert_eq!(induced.nodes(), [4, 1, 3]);
+        assert!(induced.has_edge(&1, &3));
+        assert!(!induced.contains(&2));
+        assert_eq!(induced.sort_stable().unwrap(), [1, 3, 4]);
+        assert!(!induced.respects(&[4, 1, 3]));
+    }
+
+    #[test]
+    fn reports_cycle() {
+        let dag: Dag<u32> = [(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]
+            .into_iter()
+            .collect();
+        let error = dag.sort().unwrap_err();
+        assert_eq!(
+            error,
+            CycleError {
+                cycle: vec![1, 2, 3]
+            }
+        );
+        assert_eq!(error.to_string(), "cycle: 1 -> 2 -> 3 -> 1");
+        assert_eq!(dag.induced([0, 1, 4]).find_cycle(), None);
+    }
+}
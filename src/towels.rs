@@ -0,0 +1,188 @@
+//! Towel arrangements from day 19.
+use std::collections::HashMap;
+
+/// Prefix tree over the towel patterns, matching all patterns a text starts with in one walk.
+#[derive(Debug, Default)]
+pub struct Trie {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    /// Whether a pattern ends here.
+    terminal: bool,
+}
+
+impl Trie {
+    pub fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut trie = Trie {
+            nodes: vec![Node::default()],
+        };
+        for pattern in patterns {
+            let mut node = 0;
+            for &b in pattern.as_bytes() {
+                node = match trie.nodes[node].children.get(&b) {
+                    Some(&child) => child,
+                    None => {
+                        trie.nodes.push(Node::default());
+                        let child = trie.nodes.len() - 1;
+                        trie.nodes[node].children.insert(b, child);
+                        child
+                    }
+                };
+            }
+            trie.nodes[node].terminal = true;
+        }
+        trie
+    }
+
+    /// Lengths of the patterns that `text` starts with, shortest first.
+    pub fn prefixes(&self, text: &[u8]) -> Vec<usize> {
+        let mut lengths = vec![];
+        let mut node = 0;
+        for (i, b) in text.iter().enumerate() {
+            match self.nodes[node].children.get(b) {
+                Some(&child) => node = child,
+                None => break,
+            }
+            if self.nodes[node].terminal {
+                lengths.push(i + 1);
+            }
+        }
+        lengths
+    }
+
+    pub fn arrange<'a>(&self, design: &'a str) -> Arrangements<'a> {
+        let bytes = design.as_bytes();
+        let matches: Vec<Vec<usize>> = (0..bytes.len())
+            .map(|i| self.prefixes(&bytes[i..]))
+            .collect();
+        let mut ways = vec![0; bytes.len() + 1];
+        ways[bytes.len()] = 1;
+        for i in (0..bytes.len()).rev() {
+            ways[i] = matches[i].iter().map(|len| ways[i + len]).sum();
+        }
+        Arrangements {
+            design,
+            matches,
+            ways,
+        }
+    }
+}
+
+/// Ways to build one design, indexed by position in the design.
+#[derive(Debug)]
+pub struct Arrangements<'a> {
+    design: &'a str,
+    /// Lengths of the patterns matching at each position.
+    matches: Vec<Vec<usize>>,
+    /// Number of arrangements of the design from each position to its end.
+    ways: Vec<usize>,
+}
+
+impl<'a> Arrangements<'a> {
+    pub fn count(&self) -> usize {
+        self.ways[0]
+    }
+
+    /// Up to `k` arrangements, preferring shorter towels first.
+    pub fn list(&self, k: usize) -> Vec<Vec<&'a str>> {
+        let mut found = vec![];
+        self.collect(0, &mut vec![], k, &mut found);
+        found
+    }
+
+    fn collect(
+        &self,
+        i: usize,
+        towels: &mut Vec<&'a str>,
+        k: usize,
+        found: &mut Vec<Vec<&'a str>>,
+    ) {
+        if i == self.design.len() {
+            found.push(towels.clone());
+            return;
+        }
+        for &len in &self.matches[i] {
+            if found.len() == k {
+                return;
+            }
+            if self.ways[i + len] > 0 {
+                towels.push(&self.design[i..i + len]);
+                self.collect(i + len, towels, k, found);
+                towels.pop();
+            }
+        }
+    }
+
+    /// Arrangement using the fewest towels.
+    pub fn shortest(&self) -> Option<Vec<&'a str>> {
+        self.extreme(|a, b| a < b)
+    }
+
+    /// Arrangement using the most towels.
+    pub fn longest(&self) -> Option<Vec<&'a str>> {
+        self.extreme(|a, b| a > b)
+    }
+
+    /// Finds the arrangement whose towel count is preferred by `better`, with a second DP over
+    /// positions holding the best count and the towel length achieving it.
+    fn extreme(&self, better: impl Fn(usize, usize) -> bool) -> Option<Vec<&'a str>> {
+        let n = self.design.len();
+        let mut best: Vec<Option<(usize, usize)>> = vec![None; n + 1];
+        best[n] = Some((0, 0));
+        for i in (0..n).rev() {
+            for &len in &self.matches[i] {
+                if let Some((count, _)) = best[i + len] {
+                    if best[i].is_none_or(|(current, _)| better(count + 1, current)) {
+                        best[i] = Some((count + 1, len));
+                    }
+                }
+            }
+        }
+        best[0]?;
+        let mut towels = vec![];
+        let mut i = 0;
+        while i < n {
+            let (_, len) = best[i]?;
+            towels.push(&self.design[i..i + len]);
+            i += len;
+        }
+        Some(towels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Trie;
+
+    #[test]
+    fn lists_decompositions() {
+        let trie = Trie::new(["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
+
+        let gbbr = trie.arrange("gbbr");
+        assert_eq!(gbbr.count(), 4);
+        assert_eq!(
+            gbbr.list(10),
+            [
+                vec!["g", "b", "b", "r"],
+                vec!["g", "b", "br"],
+                vec!["gb", "b", "r"],
+                vec!["gb", "br"],
+            ]
+        );
+        assert_eq!(gbbr.list(1), [vec!["g", "b", "b", "r"]]);
+        assert_eq!(gbbr.shortest(), Some(vec!["gb", "br"]));
+        assert_eq!(gbbr.longest(), Some(vec!["g", "b", "b", "r"]));
+
+        let rrbgbr = trie.arrange("rrbgbr");
+        assert_eq!(rrbgbr.count(), 6);
+        assert_eq!(rrbgbr.shortest(), Some(vec!["r", "rb", "g", "br"]));
+
+        let ubwu = trie.arrange("ubwu");
+        assert_eq!(ubwu.count(), 0);
+        assert!(ubwu.list(5).is_empty());
+        assert_eq!(ubwu.shortest(), None);
+    }
+}
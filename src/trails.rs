@@ -0,0 +1,173 @@
+//! The hiking trails of the topographic map from day 10.
+use crate::grid::{Grid, Point};
+
+pub const TRAILHEAD: u8 = 0;
+pub const PEAK: u8 = 9;
+
+/// Both measures of a trailhead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Scores {
+    /// Number of peaks reachable, for part one.
+    pub score: usize,
+    /// Number of distinct trails, for part two.
+    pub rating: u64,
+}
+
+/// Heights of the map, `None` for the impassable `.` tiles of the puzzle examples.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopographicMap {
+    pub heights: Grid<Option<u8>>,
+}
+
+pub fn parse(input: &str) -> TopographicMap {
+    TopographicMap {
+        heights: Grid::parse(input).map(|c| c.to_digit(10).map(|h| h as u8)),
+    }
+}
+
+impl TopographicMap {
+    /// Neighbours of `point` one step higher.
+    fn uphill(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        let next = self.heights[point].map(|h| h + 1);
+        self.heights
+            .neighbours(point)
+            .filter(move |&n| next.is_some() && self.heights[n] == next)
+    }
+
+    pub fn trailheads(&self) -> impl Iterator<Item = Point> + '_ {
+        self.heights
+            .iter()
+            .filter(|(_, &h)| h == Some(TRAILHEAD))
+            .map(|(point, _)| point)
+    }
+
+    /// Scores of every trailhead, in row-major order.
+    ///
+    /// Goes down from the peaks one height at a time. Each cell adds up the ratings of the cells
+    /// one step uphill, and joins their sets of reachable peaks kept as bitsets, so both scores
+    /// come out of the same pass without following any trail.
+    pub fn scores(&self) -> Vec<(Point, Scores)> {
+        let peaks = self.heights.map(|&h| h == Some(PEAK));
+        let peak_ids: Vec<Point> = peaks.iter().filter(|(_, &p)| p).map(|(p, _)| p).collect();
+        let words = peak_ids.len().div_ceil(64);
+
+        let mut levels = vec![vec![]; usize::from(PEAK) + 1];
+        for (point, &height) in self.heights.iter() {
+            if let Some(h) = height.filter(|&h| h <= PEAK) {
+                levels[usize::from(h)].push(point);
+            }
+        }
+
+        let mut reachable = self.heights.map(|_| vec![0u64; words]);
+        let mut rating = self.heights.map(|_| 0u64);
+        for (id, &peak) in peak_ids.iter().enumerate() {
+            reachable[peak][id / 64] |= 1 << (id % 64);
+            rating[peak] = 1;
+        }
+        for level in levels[..usize::from(PEAK)].iter().rev() {
+            for &point in level {
+                let mut peaks = vec![0u64; words];
+                for next in self.uphill(point) {
+                    for (word, other) in peaks.iter_mut().zip(&reachable[next]) {
+                        *word |= other;
+                    }
+                    rating[point] += rating[next];
+                }
+                reachable[point] = peaks;
+            }
+        }
+
+        self.trailheads()
+            .map(|point| {
+                let score = reachable[point]
+                    .iter()
+                    .map(|word| word.count_ones() as usize)
+                    .sum();
+                let rating = rating[point];
+                (point, Scores { score, rating })
+            })
+            .collect()
+    }
+
+    /// Every trail from `trailhead` to a peak, from the trailhead up.
+    pub fn trails(&self, trailhead: Point) -> Vec<Vec<Point>> {
+        let mut trails = vec![];
+        if self.heights[trailhead] == Some(TRAILHEAD) {
+            self.extend(&mut vec![trailhead], &mut trails);
+        }
+        trails
+    }
+
+    fn extend(&self, trail: &mut Vec<Point>, trails: &mut Vec<Vec<Point>>) {
+        let last = *trail.last().expect("trails start at their trailhead");
+        if self.heights[last] == Some(PEAK) {
+            trails.push(trail.clone());
+            return;
+        }
+        for next in self.uphill(last) {
+            trail.push(next);
+            self.extend(trail, trails);
+            trail.pop();
+        }
+    }
+
+    /// The map showing only the heights along `trail`, as drawn in the puzzle text.
+    pub fn draw(&self, trail: &[Point]) -> String {
+        let mut cells = self.heights.map(|_| '.');
+        for &point in trail {
+            if let Some(h) = self.heights[point] {
+                cells[point] = char::from(b'0' + h);
+            }
+        }
+        cells.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, TopographicMap};
+    use crate::grid::Point;
+
+    const EXAMPLE: &str =
+        "89010123\n78121874\n87430965\n96549874\n45678903\n32019012\n01329801\n10456732\n";
+
+    fn summary(map: &TopographicMap) -> Vec<(Point, usize, u64)> {
+        let scores = map.scores();
+        scores
+            .iter()
+            .map(|&(p, s)| (p, s.score, s.rating))
+            .collect()
+    }
+
+    #[test]
+    fn lists_drawn_trails() {
+        let map = parse(".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....\n");
+        let trails = map.trails((5, 0));
+        let drawn: Vec<String> = trails.iter().map(|trail| map.draw(trail)).collect();
+        assert_eq!(
+            drawn,
+            [
+                ".....0.\n.....1.\n.....2.\n.....3.\n.....4.\n..8765.\n..9....\n",
+                ".....0.\n.....1.\n.....2.\n..6543.\n..7....\n..8....\n..9....\n",
+                ".....0.\n..4321.\n..5....\n..6....\n..7....\n..8....\n..9....\n",
+            ]
+        );
+        assert_eq!(summary(&map), [((5, 0), 1, 3)]);
+    }
+
+    #[test]
+    fn scores_match_listed_trails() {
+        let map = parse("10..9..\n2...8..\n3...7..\n4567654\n...8..3\n...9..2\n.....01\n");
+        assert_eq!(summary(&map), [((1, 0), 1, 1), ((5, 6), 2, 2)]);
+
+        let example = parse(EXAMPLE);
+        for (trailhead, scores) in example.scores() {
+            let trails = example.trails(trailhead);
+            assert_eq!(trails.len() as u64, scores.rating);
+            let mut peaks: Vec<_> = trails.iter().map(|trail| trail[9]).collect();
+            peaks.sort();
+            peaks.dedup();
+            assert_eq!(peaks.len(), scores.score);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! The box pushing robot from day 15.
+use itertools::Itertools;
+
+use crate::replay::Replay;
+
+pub fn parse_input(input: &str) -> ((usize, usize), Vec<Vec<char>>, Vec<char>) {
+    let input_blocks = input.split("\n\n").collect::<Vec<&str>>();
+    let grid: Vec<Vec<char>> = input_blocks[0]
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect();
+    let mut user = (0, 0);
+
+    for (y, row) in grid.iter().enumerate() {
+        for (x, c) in row.iter().enumerate() {
+            if *c == '@' {
+                user = (x, y);
+            }
+        }
+    }
+    let moves = input_blocks[1].lines().join("").chars().collect();
+    (user, grid, moves)
+}
+
+/// The warehouse with everything except the robot twice as wide.
+pub fn parse_input_2(input: &str) -> ((usize, usize), Vec<Vec<char>>, Vec<char>) {
+    let (mut user, grid, moves) = parse_input(input);
+
+    let mut new_grid = vec![vec!['.'; grid[0].len() * 2]; grid.len()];
+    for (y, row) in grid.iter().enumerate() {
+        for (x, c) in row.iter().enumerate() {
+            match c {
+                '.' => {
+                    new_grid[y][2 * x] = '.';
+                    new_grid[y][2 * x + 1] = '.';
+                }
+                '#' => {
+                    new_grid[y][2 * x] = '#';
+                    new_grid[y][2 * x + 1] = '#';
+                }
+                'O' => {
+                    new_grid[y][2 * x] = '[';
+                    new_grid[y][2 * x + 1] = ']';
+                }
+                '@' => {
+                    new_grid[y][2 * x] = '@';
+                    new_grid[y][2 * x + 1] = '.';
+                }
+                _ => panic!("Invalid character"),
+            }
+        }
+    }
+
+    user = (user.0 * 2, user.1);
+    (user, new_grid, moves)
+}
+
+pub fn move_once(user: (usize, usize), grid: &mut [Vec<char>], direction: char) -> (usize, usize) {
+    let (x, y) = user;
+    let (x, y) = (x as i32, y as i32);
+    let (dx, dy) = match direction {
+        '^' => (0, -1i32),
+        'v' => (0, 1),
+        '<' => (-1i32, 0),
+        '>' => (1, 0),
+        _ => panic!("Invalid direction"),
+    };
+
+    match grid[(y + dy) as usize][(x + dx) as usize] {
+        '.' => {
+            grid[(y + dy) as usize][(x + dx) as usize] = '@';
+            grid[(y) as usize][(x) as usize] = '.';
+            ((x + dx) as usize, (y + dy) as usize)
+        }
+        '#' => (x as usize, y as usize),
+        'O' => {
+            let mut k = 1;
+            while grid[(y + dy * k) as usize][(x + dx * k) as usize] == 'O' {
+                k += 1;
+            }
+            match grid[(y + dy * k) as usize][(x + dx * k) as usize] {
+                '.' => {
+                    grid[(y + dy * k) as usize][(x + dx * k) as usize] = 'O';
+                    grid[(y + dy) as usize][(x + dx) as usize] = '@';
+                    grid[(y) as usize][(x) as usize] = '.';
+                    ((x + dx) as usize, (y + dy) as usize)
+                }
+                '#' => (x as usize, y as usize),
+                _ => panic!("Invalid character"),
+            }
+        }
+        _ => panic!("Invalid character"),
+    }
+}
+
+pub fn score(grid: &[Vec<char>]) -> usize {
+    let mut score = 0;
+    for (i, row) in grid.iter().enumerate() {
+        for (j, c) in row.iter().enumerate() {
+            if *c == 'O' || *c == '[' {
+                score += 100 * i + j;
+            }
+        }
+    }
+    score
+}
+
+fn can_move(pos: (i32, i32), dir: (i32, i32), grid: &[Vec<char>]) -> bool {
+    let (x, y) = pos;
+    let (dx, dy) = dir;
+
+    match grid[y as usize][x as usize] {
+        '@' => can_move((x + dx, y + dy), dir, grid),
+        '.' => true,
+        '#' => false,
+        '[' => {
+            can_move((x + dx, y + dy), dir, grid)
+                && (dy == 0 || can_move((x + dx + 1, y + dy), dir, grid))
+        }
+        ']' => {
+            can_move((x + dx, y + dy), dir, grid)
+                && (dy == 0 || can_move((x + dx - 1, y + dy), dir, grid))
+        }
+        _ => panic!("Invalid character"),
+    }
+}
+
+pub fn move_once_2(
+    user: (usize, usize),
+    grid: &mut [Vec<char>],
+    direction: char,
+) -> (usize, usize) {
+    let (x, y) = user;
+    let (x, y) = (x as i32, y as i32);
+    let (dx, dy) = match direction {
+        '^' => (0, -1i32),
+        'v' => (0, 1),
+        '<' => (-1i32, 0),
+        '>' => (1, 0),
+        _ => panic!("Invalid direction"),
+    };
+    if can_move((x, y), (dx, dy), grid) {
+        apply_move((x, y), grid, (dx, dy));
+        return ((x + dx) as usize, (y + dy) as usize);
+    }
+    (x as usize, y as usize)
+}
+
+fn apply_move(pos: (i32, i32), grid: &mut [Vec<char>], dir: (i32, i32)) {
+    let (x, y) = pos;
+    let (dx, dy) = dir;
+
+    match grid[y as usize][x as usize] {
+        '@' => {
+            apply_move((x + dx, y + dy), grid, dir);
+            grid[y as usize][x as usize] = '.';
+            grid[(y + dy) as usize][(x + dx) as usize] = '@';
+        }
+        '[' => {
+            apply_move((x + dx, y + dy), grid, dir);
+            grid[y as usize][x as usize] = '.';
+            grid[(y + dy) as usize][(x + dx) as usize] = '[';
+
+            if dy != 0 {
+                apply_move((x + dx + 1, y + dy), grid, dir);
+                grid[(y + dy) as usize][(x + dx + 1) as usize] = ']';
+                grid[y as usize][(x + 1) as usize] = '.';
+            }
+        }
+        ']' => {
+            apply_move((x + dx, y + dy), grid, dir);
+            grid[y as usize][x as usize] = '.';
+            grid[(y + dy) as usize][(x + dx) as usize] = ']';
+
+            if dy != 0 {
+                apply_move((x + dx - 1, y + dy), grid, dir);
+                grid[(y + dy) as usize][(x + dx - 1) as usize] = '[';
+                grid[y as usize][(x - 1) as usize] = '.';
+            }
+        }
+        _ => (),
+    }
+}
+
+fn render(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Runs all moves of `input` with [`move_once`], or [`move_once_2`] in the doubled-width
+/// warehouse when `wide` is set, recording the initial state and the state after every move.
+pub fn record(input: &str, wide: bool) -> Replay {
+    let (mut user, mut grid, moves) = if wide {
+        parse_input_2(input)
+    } else {
+        parse_input(input)
+    };
+    let move_once = if wide { move_once_2 } else { move_once };
+
+    let mut replay = Replay::new();
+    replay.record("Initial state", render(&grid));
+    for (i, &direction) in moves.iter().enumerate() {
+        let before = user;
+        user = move_once(user, &mut grid, direction);
+        let caption = format!(
+            "Move {} {direction}{}",
+            i + 1,
+            if user == before { " (blocked)" } else { "" }
+        );
+        replay.record(caption, render(&grid));
+    }
+    replay
+}
+
+#[cfg(test)]
+mod tests {
+    use super::record;
+
+    const SMALL: &str = "\
+#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^
+";
+
+    #[test]
+    fn records_wide_moves() {
+        let replay = record(SMALL, true);
+        assert_eq!(replay.len(), 12);
+        assert_eq!(replay.frames()[1].caption, "Move 1 <");
+        assert_eq!(
+            replay.frames()[3].content,
+            "##############\n##......##..##\n##..........##\n##...[][]...##\n##....[]....##\n##.......@..##\n##############\n"
+        );
+        assert_eq!(
+            replay.frames()[11].content,
+            "##############\n##...[].##..##\n##...@.[]...##\n##....[]....##\n##..........##\n##..........##\n##############\n"
+        );
+        assert_eq!(replay.frames()[7].caption, "Move 7 ^ (blocked)");
+    }
+}
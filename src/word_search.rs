@@ -0,0 +1,260 @@
+//! The word search of day 4, generalised to any word or two-dimensional template.
+use crate::grid::{Grid, Point};
+use crate::template::{ANSI_BOLD, ANSI_RESET, ANSI_YELLOW};
+
+/// Character of a template matching any letter.
+pub const WILDCARD: char = '.';
+
+/// Rectangle of letters to look for, `None` cells matching anything.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Template {
+    pub cells: Grid<Option<char>>,
+}
+
+impl Template {
+    /// Reads a template drawn as a small grid, [`WILDCARD`] standing for any letter.
+    pub fn parse(drawing: &str) -> Self {
+        let cells = Grid::parse(drawing).map(|&c| (c != WILDCARD).then_some(c));
+        Self { cells }
+    }
+
+    /// The word written left to right.
+    pub fn line(word: &str) -> Self {
+        let letters: Vec<_> = word.chars().map(Some).collect();
+        Self {
+            cells: Grid::from_vec(letters.len(), 1, letters),
+        }
+    }
+
+    /// The word written from the top left corner to the bottom right corner.
+    pub fn diagonal(word: &str) -> Self {
+        let letters: Vec<char> = word.chars().collect();
+        let n = letters.len();
+        let mut cells = Grid::new(n, n, None);
+        for (i, &c) in letters.iter().enumerate() {
+            cells[(i, i)] = Some(c);
+        }
+        Self { cells }
+    }
+
+    /// The template turned a quarter clockwise.
+    pub fn rotate(&self) -> Self {
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let mut cells = Grid::new(height, width, None);
+        for ((x, y), &c) in self.cells.iter() {
+            cells[(height - 1 - y, x)] = c;
+        }
+        Self { cells }
+    }
+
+    /// The template mirrored left to right.
+    pub fn reflect(&self) -> Self {
+        let width = self.cells.width();
+        let mut cells = self.cells.clone();
+        for ((x, y), &c) in self.cells.iter() {
+            cells[(width - 1 - x, y)] = c;
+        }
+        Self { cells }
+    }
+
+    /// The template without its border rows and columns holding only wildcards.
+    pub fn trim(&self) -> Self {
+        let letters: Vec<Point> = self
+            .cells
+            .iter()
+            .filter(|(_, c)| c.is_some())
+            .map(|(point, _)| point)
+            .collect();
+        let Some(&(mut left, mut top)) = letters.first() else {
+            return Self {
+                cells: Grid::new(0, 0, None),
+            };
+        };
+        let (mut right, mut bottom) = (left, top);
+        for &(x, y) in &letters {
+            (left, right) = (left.min(x), right.max(x));
+            (top, bottom) = (top.min(y), bottom.max(y));
+        }
+        let mut cells = Grid::new(right - left + 1, bottom - top + 1, None);
+        for &(x, y) in &letters {
+            cells[(x - left, y - top)] = self.cells[(x, y)];
+        }
+        Self { cells }
+    }
+
+    /// The distinct templates obtained by rotating and reflecting this one, itself first, with
+    /// their wildcard-only borders trimmed.
+    ///
+    /// Symmetric templates have fewer than eight, so that each occurrence is matched once.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations: Vec<Self> = vec![];
+        for start in [self.trim(), self.trim().reflect()] {
+            let mut template = start;
+            for _ in 0..4 {
+                let next = template.rotate();
+                if !orientations.contains(&template) {
+                    orientations.push(template);
+                }
+                template = next;
+            }
+        }
+        orientations
+    }
+}
+
+/// Set of templates searched together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    pub templates: Vec<Template>,
+}
+
+impl Pattern {
+    /// The word in any of the 8 directions, once only for palindromes.
+    pub fn word(word: &str) -> Self {
+        let mut templates = Template::line(word).orientations();
+        for template in Template::diagonal(word).orientations() {
+            if !templates.contains(&template) {
+                templates.push(template);
+            }
+        }
+        Self { templates }
+    }
+
+    /// The template drawn in `drawing` in any rotation or reflection.
+    pub fn template(drawing: &str) -> Self {
+        Self {
+            templates: Template::parse(drawing).orientations(),
+        }
+    }
+}
+
+/// One occurrence of a pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the matching template in [`Pattern::templates`].
+    pub template: usize,
+    /// Top left corner of the template on the grid.
+    pub origin: Point,
+    /// Cells matched by letters of the template, in row-major order of the template.
+    pub cells: Vec<Point>,
+}
+
+/// Every occurrence of `pattern` in `grid`, by template then in row-major order of the origin.
+///
+/// Templates without any letter, such as the one of an empty word, match nothing.
+pub fn find(grid: &Grid<char>, pattern: &Pattern) -> Vec<Match> {
+    let mut matches = vec![];
+    for (index, template) in pattern.templates.iter().enumerate() {
+        let (width, height) = (template.cells.width(), template.cells.height());
+        if width > grid.width() || height > grid.height() {
+            continue;
+        }
+        let letters: Vec<(Point, char)> = template
+            .cells
+            .iter()
+            .filter_map(|(point, c)| c.map(|c| (point, c)))
+            .collect();
+        if letters.is_empty() {
+            continue;
+        }
+        for y in 0..=grid.height() - height {
+            for x in 0..=grid.width() - width {
+                if letters
+                    .iter()
+                    .all(|&((dx, dy), c)| grid[(x + dx, y + dy)] == c)
+                {
+                    matches.push(Match {
+                        template: index,
+                        origin: (x, y),
+                        cells: letters
+                            .iter()
+                            .map(|&((dx, dy), _)| (x + dx, y + dy))
+                            .collect(),
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// The grid with the matched letters highlighted when `ansi` is set, or with every other
+/// letter replaced by `.` as drawn in the puzzle text otherwise.
+pub fn highlight(grid: &Grid<char>, matches: &[Match], ansi: bool) -> String {
+    let mut matched = grid.map(|_| false);
+    for &cell in matches.iter().flat_map(|m| &m.cells) {
+        matched[cell] = true;
+    }
+    let mut out = String::new();
+    for (row, flags) in grid.rows().zip(matched.rows()) {
+        for (&c, &m) in row.iter().zip(flags) {
+            match (ansi, m) {
+                (true, true) => out += &format!("{ANSI_BOLD}{ANSI_YELLOW}{c}{ANSI_RESET}"),
+                (false, false) => out.push('.'),
+                _ => out.push(c),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, highlight, Pattern, Template};
+    use crate::grid::Grid;
+
+    const EXAMPLE: &str = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\nXXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX\n";
+
+    #[test]
+    fn finds_words_in_all_directions() {
+        let grid = Grid::parse(EXAMPLE);
+        let matches = find(&grid, &Pattern::word("XMAS"));
+        assert_eq!(matches.len(), 18);
+        assert_eq!(
+            highlight(&grid, &matches, false),
+            "....XXMAS.\n.SAMXMS...\n...S..A...\n..A.A.MS.X\nXMASAMX.MM\nX.....XA.A\nS.S.S.S.SS\n.A.A.A.A.A\n..M.M.M.MM\n.X.X.XMASX\n"
+        );
+        assert_eq!(Pattern::word("XMAS").templates.len(), 8);
+        assert_eq!(Pattern::word("ABA").templates.len(), 4);
+        assert_eq!(Pattern::word("A").templates.len(), 1);
+    }
+
+    #[test]
+    fn finds_templates_in_any_orientation() {
+        let grid = Grid::parse(EXAMPLE);
+        let matches = find(&grid, &Pattern::template("M.S\n.A.\nM.S\n"));
+        assert_eq!(matches.len(), 9);
+        assert_eq!(
+            highlight(&grid, &matches, false),
+            ".M.S......\n..A..MSMS.\n.M.S.MAA..\n..A.ASMSM.\n.M.S.M....\n..........\nS.S.S.S.S.\n.A.A.A.A..\nM.M.M.M.M.\n..........\n"
+        );
+        assert_eq!(matches[0].cells, [(1, 0), (3, 0), (2, 1), (1, 2), (3, 2)]);
+
+        let l = Template::parse("X.\nXX\n");
+        assert_eq!(l.orientations().len(), 4);
+        assert_eq!(l.rotate().rotate().rotate().rotate(), l);
+        assert_eq!(Template::parse("AB\n").orientations().len(), 4);
+        assert_eq!(
+            Template::parse("A.\n.B\n").reflect(),
+            Template::parse(".A\nB.\n")
+        );
+    }
+
+    #[test]
+    fn empty_patterns_match_nothing() {
+        let grid = Grid::parse(EXAMPLE);
+        assert!(find(&grid, &Pattern::word("")).is_empty());
+        assert!(find(&grid, &Pattern::template("..\n..\n")).is_empty());
+    }
+
+    #[test]
+    fn matches_padded_templates_once() {
+        let grid = Grid::parse("AB\nCD\n");
+        let matches = find(&grid, &Pattern::template("A.\n"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].cells, [(0, 0)]);
+        assert_eq!(Pattern::template(".A.\n...\n").templates.len(), 1);
+        assert_eq!(Template::parse(".A\n..\n").trim(), Template::parse("A\n"));
+    }
+}